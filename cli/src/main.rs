@@ -3,6 +3,7 @@
  */
 
 mod tui;
+mod profiles;
 
 use std::{
     io,
@@ -12,8 +13,13 @@ use std::{
     },
 };
 use clap::{
+    parser::ValueSource,
     ArgGroup,
+    ArgMatches,
+    CommandFactory,
+    FromArgMatches,
     Parser,
+    Subcommand,
 };
 use crossterm::{
     event,
@@ -34,32 +40,80 @@ use crossterm::{
 };
 use ratatui::{
     backend::CrosstermBackend,
+    widgets::Widget,
     Terminal,
 };
+use std::fs;
 
 use core::{
     Config,
     GameMode,
+    Language,
     Level,
+    Response,
     RawResults,
     process_results,
     language_from_str,
-    SCHEMES_DIR,
+    find_pack,
+    PackKind,
     generate_content,
+    generate_quote,
+    generate_stream,
+    generate_practice_stream,
+    GeneratorResponse,
+    StreamResponse,
+    generate_practice,
+    generate_drill,
+    weak_targets,
+    aggregate_errors,
+    last_n,
+    random_seed,
+    today,
     list_languages,
     list_schemes,
+    list_quotes,
+    search_quotes,
     validate_config,
-    Test
+    Test,
+    HistoryRecord,
+    TestKind,
+    HistoryStore,
+    JsonlStore,
+    Recording,
+    Replay,
+    replay,
+    Clock,
+    SystemClock,
+    WordStream,
+    Quote,
+    QuoteLength,
+    TextFormat,
+    Passage,
+    BookmarkStore,
+    EndReason,
+    generate_passage
 };
 
 use tui::{
     TestView,
     ResultView,
     StartView,
-    load_scheme_file
+    StatsView,
+    Settings,
+    SettingsView,
+    load_scheme_file,
+    load_scheme_css
 };
 
-use core::results::{Key};
+use core::results::{
+    FinalResults,
+    Key
+};
+
+use profiles::{
+    ConfigFile,
+    FileOptions
+};
 
 fn convert_key(key: &KeyEvent) -> Key {
     if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
@@ -71,6 +125,7 @@ fn convert_key(key: &KeyEvent) -> Key {
         KeyCode::Backspace => Key::Backspace,
         KeyCode::Esc => Key::Escape,
         KeyCode::Char(' ') => Key::Space,
+        KeyCode::Tab => Key::Tab,
         KeyCode::Char(c) => Key::Char(c),
         other => Key::Other(format!("{:?}", other)),
     }
@@ -78,8 +133,10 @@ fn convert_key(key: &KeyEvent) -> Key {
 
 
 const STYLE_ERROR: &str = "\x1b[1;31merror:\x1b[0m";        // 1;31 = bold red, 0m = reset
-const _STYLE_WARNING: &str = "\x1b[1;33mwarning:\x1b[0m";    // bold yellow
-const _STYLE_INFO: &str = "\x1b[1;32minfo:\x1b[0m";          // bold green
+const STYLE_WARNING: &str = "\x1b[1;33mwarning:\x1b[0m";    // bold yellow
+const STYLE_INFO: &str = "\x1b[1;32minfo:\x1b[0m";          // bold green
+
+const DRILL_REPEAT: usize = 3;      // how many times every missed word is repeated in drill
 
 
 #[derive(Debug, Parser)]
@@ -90,7 +147,7 @@ const _STYLE_INFO: &str = "\x1b[1;32minfo:\x1b[0m";          // bold green
 )]
 #[command(group(
     ArgGroup::new("mode")
-        .args(&["words", "quote", "zen"])
+        .args(&["words", "quote", "zen", "book", "code"])
         .multiple(false)
 ))]
 #[command(group(
@@ -105,11 +162,14 @@ const _STYLE_INFO: &str = "\x1b[1;32minfo:\x1b[0m";          // bold green
 ))]
 #[command(group(
     ArgGroup::new("listing")
-        .args(&["list_languages", "list_schemes"])
+        .args(&["list_languages", "list_schemes", "list_quotes"])
         .multiple(false)
 ))]
 struct Opt {
-    /// List available languages
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// List available languages, of quote or code mode with --quote or --code
     #[arg(long = "list-languages")]
     list_languages: bool,
 
@@ -117,6 +177,10 @@ struct Opt {
     #[arg(long = "list-schemes")]
     list_schemes: bool,
 
+    /// List quotes of language, matching query if given
+    #[arg(long = "list-quotes", value_name = "query", num_args = 0..=1, default_missing_value = "")]
+    list_quotes: Option<String>,
+
     /// Enable words mode [default]
     #[arg(short, long)]
     words: bool,
@@ -129,6 +193,22 @@ struct Opt {
     #[arg(short, long)]
     zen: bool,
 
+    /// Enable book mode, type through text file in passages of word count
+    #[arg(long, value_name = "path", conflicts_with = "language_file")]
+    book: Option<String>,
+
+    /// Enable code mode, type source snippets with indentation
+    #[arg(long)]
+    code: bool,
+
+    /// Enable code mode with blocks of source files in directory, .gitignore is respected
+    #[arg(long = "from-repo", value_name = "dir", conflicts_with_all = ["words", "quote", "zen", "book", "language_file"])]
+    from_repo: Option<String>,
+
+    /// Strip comments from blocks of --from-repo
+    #[arg(long = "strip-comments")]
+    strip_comments: bool,
+
     /// Include punctuation in test text
     #[arg(short, long)]
     punctuation: bool,
@@ -145,6 +225,10 @@ struct Opt {
     #[arg(long)]
     death: bool,
 
+    /// Ignore accents when matching typed letters, e.g. a for á
+    #[arg(long = "lenient-accents")]
+    lenient_accents: bool,
+
     /// Specify test language
     #[arg(short, long, value_name = "lang", default_value = "en")]
     language: String,
@@ -153,6 +237,18 @@ struct Opt {
     #[arg(long = "language-file", value_name = "path")]
     language_file: Option<String>,
 
+    /// Read test text from file, or from standard input with -
+    #[arg(value_name = "file", conflicts_with_all = ["language", "language_file", "book", "from_repo"])]
+    text_file: Option<String>,
+
+    /// Specify markup of custom file, by its extension if not given
+    #[arg(long, value_name = "format", value_parser = ["plain", "markdown", "html"])]
+    format: Option<String>,
+
+    /// Fold typographic quotes, dashes and ellipses of custom file to ascii
+    #[arg(long)]
+    ascii: bool,
+
     /// Specify color scheme
     #[arg(short, long, value_name = "lang", default_value = "monokai")]
     scheme: String,
@@ -165,20 +261,78 @@ struct Opt {
     #[arg(short, long, value_name = "n", default_value_t = 25)]
     count: usize,
 
+    /// Pick only from n most frequent words
+    #[arg(long = "top", value_name = "n")]
+    top_words: Option<usize>,
+
+    /// Pick words by their frequency
+    #[arg(long)]
+    weighted: bool,
+
+    /// Pick only quotes of given length in quote mode
+    #[arg(long, value_name = "len", value_parser = ["short", "medium", "long", "thicc"])]
+    length: Option<String>,
+
+    /// Pick quote by id, enables quote mode unless code mode is chosen, see --list-quotes
+    #[arg(long = "quote-id", value_name = "id", conflicts_with_all = ["words", "zen", "book"])]
+    quote_id: Option<String>,
+
     /// Specify time limit
     #[arg(short, long, value_name = "sec")]
     time: Option<u32>,
+
+    /// Specify seed to reproduce test content [default: random]
+    #[arg(long, value_name = "n")]
+    seed: Option<u64>,
+
+    /// Enable daily challenge, same test for everyone today
+    #[arg(long, conflicts_with = "seed")]
+    daily: bool,
+
+    /// Practice letters mistyped most often in recent tests
+    #[arg(long = "practice-weak", conflicts_with_all = ["quote", "zen", "daily"])]
+    practice_weak: bool,
+
+    /// Save keystroke recording of the last finished test
+    #[arg(long, value_name = "path")]
+    record: Option<String>,
+
+    /// Use options of named profile from config file
+    #[arg(long, value_name = "name")]
+    profile: Option<String>,
+
+    /// Save current options as named profile to config file
+    #[arg(long = "save-profile", value_name = "name")]
+    save_profile: Option<String>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Show statistics of saved test history
+    Stats,
+
+    /// Replay recorded test
+    Replay {
+        /// Recording file
+        #[arg(value_name = "path")]
+        file: String,
+
+        /// Playback speed multiplier
+        #[arg(long, value_name = "x", default_value_t = 1.0)]
+        speed: f64,
+    },
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
 
-    // arguments parsing
-    let opt = Opt::parse();
+    // arguments parsing, matches tell which flags were given explicitly
+    let matches = Opt::command().get_matches();
+    let mut opt = Opt::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
 
     // listing = end
     if opt.list_languages || opt.list_schemes {
         let response = if opt.list_languages {
-            list_languages()
+            list_languages(if opt.quote { GameMode::Quote } else if opt.code { GameMode::Code } else { GameMode::Words })
         } else {
             list_schemes()
         };
@@ -188,32 +342,88 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             std::process::exit(1);
         }
 
-        for item in &response.payload {
-            println!("{item}");
+        // name and where it comes from
+        let width = response.payload.iter().map(|pack| pack.name.len()).max().unwrap_or(0);
+        for pack in &response.payload {
+            println!("{:<width$}  {}", pack.name, pack.source.location());
         }
 
         return Ok(());
     }
 
-    // color scheme configuration
-    if let Some(path) = &opt.scheme_file {
-        if let Err(msg) = load_scheme_file(path) {
+    // config file defaults and profile, explicit flags override them
+    let config_path = ConfigFile::default_path();
+
+    let config_file = match &config_path {
+        Some(path) => ConfigFile::load(path),
+        None if opt.profile.is_some() || opt.save_profile.is_some() => Err("cannot locate user config directory".to_string()),
+        None => Ok(ConfigFile::default()),
+    };
+
+    let file_result = config_file
+        .as_ref()
+        .map_err(|e| e.clone())
+        .and_then(|file| file.options(opt.profile.as_deref()))
+        .and_then(|options| apply_file_options(&mut opt, &matches, options));
+
+    if let Err(msg) = file_result {
+        eprintln!("{STYLE_ERROR} {msg}");
+        std::process::exit(1);
+    }
+
+    // quotes listing = end, language may come from profile or config file
+    if let Some(query) = &opt.list_quotes {
+        let response = list_quotes(&Language::Quotes(opt.language.clone()));
+
+        if let Some((Level::Error, msg)) = &response.message {
             eprintln!("{STYLE_ERROR} {msg}");
             std::process::exit(1);
         }
-    } else {
-        let path = format!("{}/{}.css", SCHEMES_DIR, opt.scheme);
-        if let Err(msg) = load_scheme_file(&path) {
+
+        // id, length and attribution
+        let quotes = search_quotes(&response.payload, query);
+        let width = quotes.iter().map(|quote| quote.id.len()).max().unwrap_or(0);
+        for quote in &quotes {
+            println!("{:<width$}  {:<6}  {}", quote.id, quote.length().as_str(), quote.attribution().unwrap_or_default());
+        }
+
+        if quotes.is_empty() {
+            eprintln!("{STYLE_INFO} no quotes match '{}'", query);
+        }
+
+        return Ok(());
+    }
+
+    // color scheme configuration, settings screen shows scheme name or its file
+    let mut scheme = opt.scheme_file.clone().unwrap_or(opt.scheme.clone());
+
+    if let Some(path) = &opt.scheme_file {
+        if let Err(msg) = load_scheme_file(path) {
             eprintln!("{STYLE_ERROR} {msg}");
             std::process::exit(1);
         }
+    } else if let Err(msg) = load_scheme(&opt.scheme) {
+        eprintln!("{STYLE_ERROR} {msg}");
+        std::process::exit(1);
+    }
+
+    // subcommands
+    match opt.command {
+        Some(Command::Stats) => return run_stats(),
+        Some(Command::Replay { file, speed }) => return run_replay(&file, speed),
+        None => {}
     }
 
     // initial config
-    let mode = if opt.quote {
+    // quote id implies quote mode, unless other mode comes from config file
+    let mode = if opt.code || opt.from_repo.is_some() {
+        GameMode::Code
+    } else if opt.quote || (opt.quote_id.is_some() && !opt.words && !opt.zen && opt.book.is_none()) {
         GameMode::Quote
     } else if opt.zen {
         GameMode::Zen
+    } else if opt.book.is_some() {
+        GameMode::Book
     } else {
         GameMode::Words
     };
@@ -221,13 +431,25 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let initial_config = Config {
         mode,
         language: language_from_str(&opt.language, mode),
-        file: opt.language_file,
+        file: opt.book.clone().or(opt.text_file.clone()).or(opt.language_file.clone()),
         word_count: opt.count,
         time_limit: opt.time,
         punctuation: opt.punctuation,
         numbers: opt.numbers,
         backtrack: !opt.strict,
         death: opt.death,
+        seed: if opt.daily { None } else { Some(opt.seed.unwrap_or_else(random_seed)) },
+        daily: opt.daily,
+        top_words: opt.top_words,
+        weighted: opt.weighted,
+        quote_length: opt.length.as_deref().and_then(QuoteLength::from_name),
+        quote_id: opt.quote_id.clone(),
+        repo: opt.from_repo.clone(),
+        strip_comments: opt.strip_comments,
+        text_format: opt.format.as_deref().and_then(TextFormat::from_name),
+        ascii: opt.ascii,
+        lenient_accents: opt.lenient_accents,
+        day: None,
     };
 
     // api config validation
@@ -238,88 +460,543 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         std::process::exit(1);
     }
 
-    let config = config_response.payload;
+    let mut config = config_response.payload;
+
+    // saving profile = end
+    if let (Some(name), Ok(mut file), Some(path)) = (&opt.save_profile, config_file, &config_path) {
+        if let Some((_, msg)) = &config_response.message {
+            eprintln!("{STYLE_WARNING} {msg}");
+        }
+
+        file.profiles.insert(name.clone(), file_options(&config, &opt));
+
+        if let Err(msg) = file.save(path) {
+            eprintln!("{STYLE_ERROR} {msg}");
+            std::process::exit(1);
+        }
+
+        println!("{STYLE_INFO} profile '{}' saved to '{}'", name, path.display());
+        return Ok(());
+    }
+
+    // user asked for exact text, new tests keep it
+    let fixed_seed = opt.seed.is_some() || config.daily;
 
-    // api words generation
-    let generation_response = generate_content(&config);
+    // api words generation, daily text is seeded by day it is generated on
+    let day_config = Config { day: config.daily.then(today), ..config.clone() };
+    let (generation_response, text) = generate_words(&day_config, opt.practice_weak);
 
     if let Some((Level::Error, msg)) = &generation_response.message {
         eprintln!("{STYLE_ERROR} {msg}");
         std::process::exit(1);
     }
 
-    let words = &generation_response.payload;
+    // api words stream, only for timed words tests
+    let stream_response = generate_session_stream(&day_config, &text.targets);
+
+    if let Some((Level::Error, msg)) = &stream_response.message {
+        eprintln!("{STYLE_ERROR} {msg}");
+        std::process::exit(1);
+    }
 
     // new test
-    let mut test = Test::new(words.clone(), &config);
+    let mut session = Session {
+        kind: text.kind(),
+        quote: text.quote,
+        passage: text.passage,
+        targets: text.targets,
+        day: day_config.day,
+        ..Session::new(generation_response.payload.clone(), stream_response.payload, &config)
+    };
 
     // entering tui
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    let mut terminal = enter_tui()?;
 
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    // choosing what warning to show
+    let mut warning_message = config_response.message.clone().or(generation_response.message.clone());
+
+    // history, recording and bookmark problems are reported after leaving tui
+    let mut history_message = None;
+    let mut record_message = None;
+    let mut bookmark_message = None;
+
+    // results of last finished test and its missed words
+    let mut final_results = FinalResults::default();
+    let mut drill_words = Vec::new();
+
+    let mut screen = Screen::Start;
+    let mut settings_back = Screen::Start;     // screen to return to when settings are left
 
-    // drawing start screen
     loop {
-        terminal.draw(|f| {
-            let size = f.area();
-            f.render_widget(StartView, size);
-        })?;
+        screen = match screen {
+            Screen::Start => match wait_key(&mut terminal, &StartView)? {
+                KeyCode::Esc => Screen::Quit,
+                KeyCode::Char('s') => {
+                    settings_back = Screen::Start;
+                    Screen::Settings
+                }
+                _ => Screen::Test,
+            },
 
-        if event::poll(Duration::from_millis(50))? {
-            match event::read()? {
-                Event::Key(_) => break,
-                Event::Resize(_, _) => continue,
-                _ => {}
+            Screen::Test => match run_test(&mut terminal, &mut session, &config, warning_message.take())? {
+                TestAction::Restart => {
+                    session.restart(&config);
+                    Screen::Test
+                }
+
+                TestAction::Quit => Screen::Quit,
+
+                TestAction::Finished => {
+                    if let Some(path) = &opt.record {
+                        let recording = Recording::new(config.clone(), session.words.clone(), &RawResults::from(&session.test));
+                        let result = recording
+                            .to_json_string()
+                            .map_err(|e| e.to_string())
+                            .and_then(|json| fs::write(path, json).map_err(|e| e.to_string()));
+
+                        if let Err(e) = result {
+                            record_message = Some(format!("cannot save recording '{}', {}", path, e));
+                        }
+                    }
+
+                    // zen mode = exit, because sensitive psyche of zen mod user will not tolerate his horrifying erroneous results
+                    if config.mode == GameMode::Zen {
+                        Screen::Quit
+                    } else {
+                        let raw_results = RawResults::from(&session.test);
+
+                        // api drill words, mistyped and slow words of this test
+                        drill_words = generate_drill(&raw_results, DRILL_REPEAT).payload;
+
+                        // api final results generation from raw test results
+                        final_results = process_results(raw_results).payload;
+
+                        // api history saving
+                        if let Some(mut store) = JsonlStore::open_default() {
+                            let words = session.test.words.iter().map(|w| w.text.clone()).collect();
+                            let record = HistoryRecord::new(config.clone(), final_results.clone(), words, session.day).with_kind(session.kind);
+                            history_message = store.save(&record).message.or(history_message);
+                        }
+
+                        // api bookmark, book continues after completed passage or words typed until time was up
+                        let typed = match session.test.end_reason {
+                            Some(EndReason::Completed) => Some(session.test.words.len()),
+                            Some(EndReason::TimeUp) => Some(session.test.current_word),
+                            _ => None,
+                        };
+                        if let (Some(passage), Some(typed), Some(mut store)) = (&session.passage, typed, BookmarkStore::open_default()) {
+                            bookmark_message = store.save(passage.bookmark_after(typed)).message.or(bookmark_message);
+                        }
+
+                        Screen::Results
+                    }
+                }
+            },
+
+            Screen::Results => {
+                let mut hints = vec![("tab", "restart"), ("enter", "next")];
+                if !drill_words.is_empty() {
+                    hints.push(("m", "missed words"));
+                }
+                hints.push(("s", "settings"));
+                hints.push(("esc", "quit"));
+
+                let view = ResultView {
+                    results: &final_results,
+                    end_reason: session.test.end_reason,
+                    seed: config.seed,
+                    daily: session.day,
+                    quote: session.quote.as_ref(),
+                    hints: &hints,
+                };
+
+                match wait_key(&mut terminal, &view)? {
+                    // same text again
+                    KeyCode::Tab => {
+                        session.restart(&config);
+                        Screen::Test
+                    }
+
+                    // new text with the same config
+                    KeyCode::Enter => {
+                        if !fixed_seed {
+                            config.seed = Some(random_seed());
+                        }
+
+                        let message;
+                        (session, message) = Session::generate(&config, opt.practice_weak);
+                        warning_message = message;
+                        Screen::Test
+                    }
+
+                    // new text from missed words, without endless stream
+                    KeyCode::Char('m') if !drill_words.is_empty() => {
+                        warning_message = Some((Level::Info, format!("practice of missed words, {} in total", drill_words.len() / DRILL_REPEAT)));
+                        session = Session::drill(std::mem::take(&mut drill_words), &config);
+                        Screen::Test
+                    }
+
+                    KeyCode::Char('s') => {
+                        settings_back = Screen::Results;
+                        Screen::Settings
+                    }
+
+                    KeyCode::Esc => Screen::Quit,
+                    _ => Screen::Results,
+                }
+            }
+
+            Screen::Settings => {
+                let mut settings = Settings::new(config.clone(), scheme.clone());
+
+                match run_settings(&mut terminal, &mut settings, &scheme)? {
+                    // new text with chosen config
+                    Some(config_response) => {
+                        config = config_response.payload;
+                        scheme = settings.scheme;
+
+                        if !fixed_seed {
+                            config.seed = Some(random_seed());
+                        }
+
+                        let message;
+                        (session, message) = Session::generate(&config, opt.practice_weak);
+                        warning_message = config_response.message.or(message);
+                        Screen::Test
+                    }
+
+                    None => settings_back,
+                }
+            }
+
+            Screen::Quit => break,
+        };
+    }
+
+    // exiting tui
+    leave_tui()?;
+
+    if let Some((_, msg)) = history_message {
+        eprintln!("{STYLE_WARNING} {msg}");
+    }
+
+    if let Some(msg) = record_message {
+        eprintln!("{STYLE_WARNING} {msg}");
+    }
+
+    if let Some((_, msg)) = bookmark_message {
+        eprintln!("{STYLE_WARNING} {msg}");
+    }
+
+    Ok(())
+}
+
+// fills options not given on command line from config file
+fn apply_file_options(opt: &mut Opt, matches: &ArgMatches, options: FileOptions) -> Result<(), String> {
+    let explicit = |id: &str| matches.value_source(id) == Some(ValueSource::CommandLine);
+
+    if !["words", "quote", "zen", "book", "code"].iter().any(|id| explicit(id)) {
+        if let Some(mode) = options.mode {
+            (opt.words, opt.quote, opt.zen, opt.code) = match mode.as_str() {
+                "words" => (true, false, false, false),
+                "quote" => (false, true, false, false),
+                "zen" => (false, false, true, false),
+                "book" => (false, false, false, false),
+                "code" => (false, false, false, true),
+                _ => return Err(format!("invalid mode '{}' in config file, expected words, quote, zen, book or code", mode)),
+            };
+
+            // book mode is chosen by its file
+            opt.book = match (mode.as_str(), options.book) {
+                ("book", Some(book)) => Some(book),
+                ("book", None) => return Err("mode 'book' in config file needs book file".to_string()),
+                _ => None,
+            };
+
+            // source tree comes with code mode
+            if !explicit("from_repo") {
+                opt.from_repo = options.from_repo.filter(|_| mode == "code");
             }
         }
     }
-    
-    // choosing what warning to show
-    let response_message = config_response.message.clone().or(generation_response.message.clone());
-    let mut warning_message = response_message.clone();
 
-    let test_start = Instant::now();
+    if !explicit("language") && !explicit("language_file") && !explicit("text_file") {
+        if let Some(language) = options.language {
+            opt.language = language;
+        }
+        opt.language_file = options.language_file.or(opt.language_file.take());
+    }
+
+    if !explicit("scheme") && !explicit("scheme_file") {
+        if let Some(scheme) = options.scheme {
+            opt.scheme = scheme;
+        }
+        opt.scheme_file = options.scheme_file.or(opt.scheme_file.take());
+    }
+
+    // text cleaning comes only with custom file
+    let has_file = opt.book.is_some() || opt.text_file.is_some() || opt.language_file.is_some();
+
+    if !explicit("format") {
+        if let Some(format) = options.format.filter(|_| has_file) {
+            if TextFormat::from_name(&format).is_none() {
+                return Err(format!("invalid text format '{}' in config file, expected plain, markdown or html", format));
+            }
+            opt.format = Some(format);
+        }
+    }
+    if !explicit("count") {
+        opt.count = options.count.unwrap_or(opt.count);
+    }
+    if !explicit("top_words") {
+        opt.top_words = options.top.or(opt.top_words);
+    }
+    if !explicit("length") {
+        if let Some(length) = options.length {
+            if QuoteLength::from_name(&length).is_none() {
+                return Err(format!("invalid quote length '{}' in config file, expected short, medium, long or thicc", length));
+            }
+            opt.length = Some(length);
+        }
+    }
+    if !explicit("quote_id") {
+        opt.quote_id = options.quote_id.or(opt.quote_id.take());
+    }
+    if !explicit("time") {
+        opt.time = options.time.or(opt.time);
+    }
+
+    // comments are stripped only with source tree of profile
+    let strip_comments = options.strip_comments.filter(|_| opt.from_repo.is_some());
+
+    // boolean flags can only be turned on from command line
+    let flags = [
+        (&mut opt.weighted, options.weighted, "weighted"),
+        (&mut opt.punctuation, options.punctuation, "punctuation"),
+        (&mut opt.numbers, options.numbers, "numbers"),
+        (&mut opt.strict, options.strict, "strict"),
+        (&mut opt.death, options.death, "death"),
+        (&mut opt.lenient_accents, options.lenient_accents, "lenient_accents"),
+        (&mut opt.strip_comments, strip_comments, "strip_comments"),
+        (&mut opt.ascii, options.ascii.filter(|_| has_file), "ascii"),
+    ];
+
+    for (flag, value, id) in flags {
+        if let (false, Some(value)) = (explicit(id), value) {
+            *flag = value;
+        }
+    }
+
+    Ok(())
+}
+
+// validated config and scheme as config file profile
+fn file_options(config: &Config, opt: &Opt) -> FileOptions {
+    FileOptions {
+        mode: Some(config.mode.as_str().to_string()),
+        language: config.file.is_none().then(|| config.language.as_str().to_string()),
+        language_file: config.file.clone().filter(|_| config.mode != GameMode::Book),
+        book: config.file.clone().filter(|_| config.mode == GameMode::Book),
+        scheme: opt.scheme_file.is_none().then(|| opt.scheme.clone()),
+        scheme_file: opt.scheme_file.clone(),
+        count: Some(config.word_count),
+        top: config.top_words,
+        weighted: Some(config.weighted),
+        length: config.quote_length.map(|length| length.as_str().to_string()),
+        quote_id: config.quote_id.clone(),
+        from_repo: config.repo.clone(),
+        strip_comments: config.repo.is_some().then_some(config.strip_comments),
+        format: config.text_format.map(|format| format.as_str().to_string()),
+        ascii: config.file.is_some().then_some(config.ascii),
+        time: config.time_limit,
+        punctuation: Some(config.punctuation),
+        numbers: Some(config.numbers),
+        strict: Some(!config.backtrack),
+        death: Some(config.death),
+        lenient_accents: Some(config.lenient_accents),
+    }
+}
+
+// cli application screens
+#[derive(Clone, Copy)]
+enum Screen {
+    Start,
+    Test,
+    Results,
+    Settings,
+    Quit,
+}
+
+// how test screen was left
+enum TestAction {
+    Finished,
+    Restart,
+    Quit,
+}
+
+// one test text with its running test
+struct Session {
+    content: Vec<String>,           // generated text, restart begins with it again
+    words: Vec<String>,             // text including streamed words
+    stream: Option<WordStream>,
+    kind: TestKind,                 // missed words practice has no stream
+    targets: Vec<String>,           // weak targets of practice, its stream is biased too
+    quote: Option<Quote>,           // picked quote in quote mode, for attribution on results
+    passage: Option<Passage>,       // passage in book mode, for progress and bookmark
+    day: Option<u64>,               // day of daily challenge text, test may end after midnight
+    test: Test,
+}
+
+impl Session {
+    fn new(content: Vec<String>, stream: Option<WordStream>, config: &Config) -> Self {
+        Self {
+            words: content.clone(),
+            test: Test::new(content.clone(), config),
+            content,
+            stream,
+            kind: TestKind::Regular,
+            targets: Vec::new(),
+            quote: None,
+            passage: None,
+            day: None,
+        }
+    }
+
+    // api generation of new text with its stream, returns message to show on test screen
+    fn generate(config: &Config, practice: bool) -> (Self, Option<(Level, String)>) {
+        let config = &Config { day: config.daily.then(today), ..config.clone() };
+        let (generation_response, text) = generate_words(config, practice);
+        let stream_response = generate_session_stream(config, &text.targets);
+
+        let message = generation_response.message.or(stream_response.message);
+        let session = Self {
+            kind: text.kind(),
+            quote: text.quote,
+            passage: text.passage,
+            targets: text.targets,
+            day: config.day,
+            ..Self::new(generation_response.payload, stream_response.payload, config)
+        };
+        (session, message)
+    }
+
+    fn drill(content: Vec<String>, config: &Config) -> Self {
+        Self { kind: TestKind::Drill, ..Self::new(content, None, config) }
+    }
+
+    // same text from the beginning, seeded stream generates the same words again
+    fn restart(&mut self, config: &Config) {
+        let config = &Config { day: self.day, ..config.clone() };
+        let stream = if self.kind == TestKind::Drill { None } else { generate_session_stream(config, &self.targets).payload };
+        *self = Self {
+            kind: self.kind,
+            targets: std::mem::take(&mut self.targets),
+            quote: self.quote.take(),
+            passage: self.passage.take(),
+            day: self.day,
+            ..Self::new(self.content.clone(), stream, config)
+        };
+    }
+}
+
+// where generated words come from, besides word lists
+#[derive(Default)]
+struct TextSource {
+    quote: Option<Quote>,
+    passage: Option<Passage>,
+    targets: Vec<String>,       // weak targets words are biased toward
+}
+
+impl TextSource {
+    // biased text is practice, unless there were no targets to bias it
+    fn kind(&self) -> TestKind {
+        if self.targets.is_empty() { TestKind::Regular } else { TestKind::Practice }
+    }
+}
+
+// api words stream, practice one keeps bias of its text
+fn generate_session_stream(config: &Config, targets: &[String]) -> StreamResponse {
+    if targets.is_empty() {
+        generate_stream(config)
+    } else {
+        generate_practice_stream(config, targets)
+    }
+}
+
+// api words generation, practice is biased toward recent mistakes, only words mode has it
+// quote, code and book modes return picked quote, snippet or passage too
+fn generate_words(config: &Config, practice: bool) -> (GeneratorResponse, TextSource) {
+    match config.mode {
+        GameMode::Quote => {
+            let response = generate_quote(config);
+            let words = response.payload.as_ref().map(Quote::words).unwrap_or_default();
+            (GeneratorResponse { payload: words, message: response.message }, TextSource { quote: response.payload, ..TextSource::default() })
+        }
+        GameMode::Code => {
+            let response = generate_quote(config);
+            let words = response.payload.as_ref().map(Quote::code_words).unwrap_or_default();
+            (GeneratorResponse { payload: words, message: response.message }, TextSource { quote: response.payload, ..TextSource::default() })
+        }
+        GameMode::Book => {
+            let response = generate_passage(config, BookmarkStore::open_default().as_ref());
+            let words = response.payload.as_ref().map(|passage| passage.words.clone()).unwrap_or_default();
+            (GeneratorResponse { payload: words, message: response.message }, TextSource { passage: response.payload, ..TextSource::default() })
+        }
+        GameMode::Words if practice => {
+            let targets = recent_weak_targets();
+            (generate_practice(config, &targets), TextSource { targets, ..TextSource::default() })
+        }
+        _ => (generate_content(config), TextSource::default()),
+    }
+}
+
+// runs test until it is complete, time is up, or hotkey is pressed
+fn run_test(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    session: &mut Session,
+    config: &Config,
+    mut warning_message: Option<(Level, String)>,
+) -> io::Result<TestAction> {
+    let warning_start = Instant::now();
+    let test = &mut session.test;
 
     // main test cycle
     loop {
         if event::poll(Duration::from_millis(10))? {
             if let Event::Key(key) = event::read()? {   // processing entered key
-                test.handle_key(convert_key(&key));
 
-                // hot keys
+                // hot keys, zen mode ends with esc and shows no results
                 if key.kind == KeyEventKind::Press {
-                    match key.code {
-                        KeyCode::Esc => break,
-                        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                            break
-                        }
+                    match convert_key(&key) {
+                        // tab types indentation in code mode, there shift+tab restarts
+                        Key::Tab if config.mode != GameMode::Code => return Ok(TestAction::Restart),
+                        Key::Other(_) if key.code == KeyCode::BackTab => return Ok(TestAction::Restart),
+                        // started test is aborted by core and shows its results, untouched one is left
+                        Key::Escape | Key::CtrlC if config.mode != GameMode::Zen && !test.started() => return Ok(TestAction::Quit),
                         _ => {}
                     }
                 }
+
+                test.handle_key(convert_key(&key));
             }
         }
 
-        // test complete = test end
-        if test.complete {
-            break;
-        }
+        // api time limit check
+        test.tick(SystemClock.now());
 
-        // time end = test end
-        let elapsed = test_start.elapsed().as_secs();
+        // api words stream refill
+        if let Some(stream) = &mut session.stream {
+            session.words.extend(stream.fill(test));
+        }
 
-        if let Some(limit) = config.time_limit {
-            let time_left = limit as i64 - elapsed as i64;
-            if time_left <= 0 {
-                break;
-            }
+        // test complete or time end = test end
+        if test.complete {
+            return Ok(TestAction::Finished);
         }
 
         // warning display - maximum priority
         if let Some(_) = warning_message {
-            if test_start.elapsed().as_secs() >= 3 {
+            if warning_start.elapsed().as_secs() >= 3 {
                 warning_message = None;
             }
         }
@@ -327,9 +1004,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         // status display
         let status_string: Option<String> = if warning_message.is_some() {  // priority - warning message
             None
-        } else if let Some(limit) = config.time_limit {     // next - time
-            let time_left = limit as i64 - elapsed as i64;
-            Some(time_left.to_string())
+        } else if let Some(remaining) = test.remaining() {     // next - time
+            Some(remaining.as_secs_f64().ceil().to_string())
         } else {                                // next - words
             if config.mode == GameMode::Zen {
                 Some(test.current_word.to_string())
@@ -339,11 +1015,17 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
         };
 
+        // book progress after words typed so far
+        let status_string = match (status_string, &session.passage) {
+            (Some(status), Some(passage)) => Some(format!("{}  book {:.1}%", status, passage.progress(test.current_word))),
+            (status, _) => status,
+        };
+
         // rendering current state
         terminal.draw(|f| {
             let size = f.area();
             let view = TestView {
-                test: &test,
+                test,
                 status: status_string.clone(),
                 warning: warning_message.clone(),
             };
@@ -351,24 +1033,247 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         })?;
 
     }
+}
+
+// edits settings until they are applied or left, returns validated config if applied
+fn run_settings(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    settings: &mut Settings,
+    current_scheme: &str,
+) -> io::Result<Option<Response<Config>>> {
+    let mut warning_message = None;
+
+    loop {
+        terminal.draw(|f| {
+            let size = f.area();
+            let view = SettingsView {
+                settings,
+                warning: warning_message.clone(),
+            };
+            f.render_widget(view, size);
+        })?;
+
+        if !event::poll(Duration::from_millis(50))? {
+            continue;
+        }
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        // message is shown until next key
+        warning_message = None;
+
+        match key.code {
+            KeyCode::Up => settings.select_prev(),
+            KeyCode::Down => settings.select_next(),
+            KeyCode::Left => settings.change(false),
+            KeyCode::Right | KeyCode::Char(' ') => settings.change(true),
+            KeyCode::Esc => return Ok(None),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(None),
+
+            KeyCode::Enter => {
+                // api config validation, errors keep settings screen open
+                let config_response = validate_config(settings.config.clone());
+
+                if let Some((Level::Error, _)) = &config_response.message {
+                    warning_message = config_response.message;
+                    continue;
+                }
+
+                if settings.scheme != current_scheme {
+                    if let Err(msg) = load_scheme(&settings.scheme) {
+                        warning_message = Some((Level::Error, msg));
+                        continue;
+                    }
+                }
+
+                return Ok(Some(config_response));
+            }
+
+            _ => {}
+        }
+    }
+}
+
+// api scheme lookup, built-in or on disk
+fn load_scheme(name: &str) -> Result<(), String> {
+    let pack = find_pack(PackKind::Schemes, name).ok_or_else(|| format!("unknown color scheme '{}'", name))?;
+    load_scheme_css(&pack.read()?)
+}
+
+// renders screen until key is pressed, returns that key
+fn wait_key<W: Widget + Clone>(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, view: &W) -> io::Result<KeyCode> {
+    loop {
+        terminal.draw(|f| {
+            let size = f.area();
+            f.render_widget(view.clone(), size);
+        })?;
+
+        if event::poll(Duration::from_millis(50))? {
+            match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => return Ok(key.code),
+                Event::Resize(_, _) => continue,
+                _ => {}
+            }
+        }
+    }
+}
+
+// most mistyped letters over last saved tests, empty if there is no history
+fn recent_weak_targets() -> Vec<String> {
+    const PRACTICE_HISTORY: usize = 20;
+    const PRACTICE_TARGETS: usize = 5;
+
+    let Some(store) = JsonlStore::open_default() else {
+        return Vec::new();
+    };
+
+    // practice and drill texts are biased already, their mistakes would feed on themselves
+    let mut records = store.load().payload;
+    records.retain(|r| r.kind == TestKind::Regular);
+    let recent = last_n(&records, PRACTICE_HISTORY).into_iter().cloned().collect::<Vec<_>>();
+
+    weak_targets(&aggregate_errors(&recent), PRACTICE_TARGETS)
+}
+
+// statistics dashboard over saved history
+fn run_stats() -> Result<(), Box<dyn std::error::Error>> {
+    let Some(store) = JsonlStore::open_default() else {
+        eprintln!("{STYLE_ERROR} cannot locate user data directory");
+        std::process::exit(1);
+    };
+
+    // api history loading
+    let history_response = store.load();
+
+    match &history_response.message {
+        Some((Level::Error, msg)) => {
+            eprintln!("{STYLE_ERROR} {msg}");
+            std::process::exit(1);
+        }
+        Some((_, msg)) => eprintln!("{STYLE_WARNING} {msg}"),
+        None => {}
+    }
+
+    let records = history_response.payload;
+
+    if records.is_empty() {
+        println!("{STYLE_INFO} no saved tests yet");
+        return Ok(());
+    }
+
+    // entering tui
+    let mut terminal = enter_tui()?;
+
+    // render stats
+    loop {
+        terminal.draw(|f| {
+            let size = f.area();
+            let view = StatsView { records: &records };
+            f.render_widget(view, size);
+        })?;
+
+        if event::poll(Duration::from_millis(50))? {
+            match event::read()? {
+                Event::Key(_) => break,
+                Event::Resize(_, _) => continue,
+                _ => {}
+            }
+        }
+    }
+
+    // exiting tui
+    leave_tui()?;
+    Ok(())
+}
+
+// animated replay of recorded test
+fn run_replay(path: &str, speed: f64) -> Result<(), Box<dyn std::error::Error>> {
+    if speed <= 0.0 {
+        eprintln!("{STYLE_ERROR} invalid playback speed '{speed}'");
+        std::process::exit(1);
+    }
+
+    let recording = match fs::read_to_string(path).map(|json| Recording::from_json_string(&json)) {
+        Ok(Ok(recording)) => recording,
+        Ok(Err(e)) => {
+            eprintln!("{STYLE_ERROR} invalid recording '{path}', {e}");
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("{STYLE_ERROR} cannot read recording '{path}', {e}");
+            std::process::exit(1);
+        }
+    };
+
+    // api replay check, reports if current key handling differs from recorded one
+    let replay_response = replay(recording.words.clone(), &recording.config, &recording.events);
+    let warning_message = replay_response.message;
+
+    let mut replay = Replay::from_recording(&recording);
+    let (first_time, _) = replay.span();
+
+    // entering tui
+    let mut terminal = enter_tui()?;
+
+    let replay_start = Instant::now();
+    let status_string = Some(format!("replay {}x", speed));
+    let mut aborted = false;
+
+    // replay cycle
+    loop {
+        let elapsed = first_time + replay_start.elapsed().mul_f64(speed);
+        replay.advance_to(elapsed);
+
+        terminal.draw(|f| {
+            let size = f.area();
+            let view = TestView {
+                test: &replay.test,
+                status: status_string.clone(),
+                warning: warning_message.clone(),
+            };
+            f.render_widget(view, size);
+        })?;
+
+        if replay.finished() {
+            break;
+        }
 
-    // zen mode = exit, because sensitive psyche of zen mod user will not tolerate his horrifying erroneous results
-    if config.mode != GameMode::Zen {
-        let raw_results = RawResults::from(&test);
+        if event::poll(Duration::from_millis(10))? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && matches!(convert_key(&key), Key::Escape | Key::CtrlC) {
+                    aborted = true;
+                    break;
+                }
+            }
+        }
+    }
 
-        // api final results generation from raw test results
-        let final_results = process_results(raw_results).payload;
+    // render results of replayed test
+    if !aborted && recording.config.mode != GameMode::Zen {
+        let final_results = process_results(RawResults::from(&replay.test)).payload;
 
-        // render results
         loop {
             terminal.draw(|f| {
                 let size = f.area();
-                let view = ResultView { results: &final_results };
+                let view = ResultView {
+                    results: &final_results,
+                    end_reason: replay.test.end_reason,
+                    seed: recording.config.seed,
+                    daily: None,
+                    quote: None,
+                    hints: &[],
+                };
                 f.render_widget(view, size);
             })?;
 
             if event::poll(Duration::from_millis(50))? {
-                match crossterm::event::read()? {
+                match event::read()? {
                     Event::Key(_) => break,
                     Event::Resize(_, _) => continue,
                     _ => {}
@@ -378,7 +1283,46 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     // exiting tui
-    disable_raw_mode()?;
-    execute!(io::stdout(), LeaveAlternateScreen)?;
+    leave_tui()?;
     Ok(())
 }
+
+fn enter_tui() -> io::Result<Terminal<CrosstermBackend<io::Stdout>>> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+
+    let backend = CrosstermBackend::new(stdout);
+    Terminal::new(backend)
+}
+
+fn leave_tui() -> io::Result<()> {
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restart_begins_same_text_again() {
+        let config = validate_config(Config { time_limit: Some(15), seed: Some(9), ..Config::default() }).payload;
+        let (mut session, _) = Session::generate(&config, false);
+        let words = session.test.words.iter().map(|word| word.text.clone()).collect::<Vec<_>>();
+        let streamed = session.stream.as_mut().map(|stream| stream.next_words(5));
+
+        session.test.handle_key(Key::Char('x'));
+        session.restart(&config);
+
+        assert!(!session.test.started());
+        assert_eq!(session.test.words.iter().map(|word| word.text.clone()).collect::<Vec<_>>(), words);
+        assert_eq!(session.stream.as_mut().map(|stream| stream.next_words(5)), streamed);
+
+        // drill is restarted as drill, without stream
+        let mut drill = Session::drill(vec!["two".to_string(); 3], &config);
+        drill.restart(&config);
+        assert_eq!(drill.kind, TestKind::Drill);
+        assert!(drill.stream.is_none());
+    }
+}
@@ -0,0 +1,189 @@
+/*
+ * cli/src/profiles.rs
+ */
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{
+        Path,
+        PathBuf
+    },
+};
+use serde::{
+    Deserialize,
+    Serialize
+};
+use core::config_dir;
+
+// options of config file, named after command line flags, missing ones are left to flags
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct FileOptions {
+    pub mode: Option<String>,           // words, quote, zen, book or code
+    pub book: Option<String>,           // text file of book mode
+    pub language: Option<String>,
+    pub language_file: Option<String>,
+    pub scheme: Option<String>,
+    pub scheme_file: Option<String>,
+    pub count: Option<usize>,
+    pub top: Option<usize>,
+    pub weighted: Option<bool>,
+    pub length: Option<String>,         // short, medium, long or thicc
+    pub quote_id: Option<String>,
+    pub from_repo: Option<String>,      // source tree of code mode
+    pub strip_comments: Option<bool>,
+    pub format: Option<String>,         // plain, markdown or html markup of custom file
+    pub ascii: Option<bool>,
+    pub time: Option<u32>,
+    pub punctuation: Option<bool>,
+    pub numbers: Option<bool>,
+    pub strict: Option<bool>,
+    pub death: Option<bool>,
+    pub lenient_accents: Option<bool>,
+}
+
+// config file with default options and named profiles
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFile {
+    #[serde(default)]
+    pub defaults: FileOptions,
+    #[serde(default)]
+    pub profiles: BTreeMap<String, FileOptions>,
+}
+
+impl ConfigFile {
+    // default location in user config directory (e.g. ~/.config/typecrab/config.toml)
+    pub fn default_path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join("config.toml"))
+    }
+
+    // missing file is the same as empty one
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(format!("cannot read config file '{}', {}", path.display(), e)),
+        };
+
+        toml::from_str(&content)
+            .map_err(|e| format!("invalid config file '{}', {}", path.display(), e.message()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let content = toml::to_string_pretty(self)
+            .map_err(|e| format!("cannot serialize config file, {}", e))?;
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("cannot create config directory '{}', {}", dir.display(), e))?;
+        }
+
+        fs::write(path, content)
+            .map_err(|e| format!("cannot write config file '{}', {}", path.display(), e))
+    }
+
+    // defaults with profile options on top of them
+    pub fn options(&self, profile: Option<&str>) -> Result<FileOptions, String> {
+        let Some(name) = profile else {
+            return Ok(self.defaults.clone());
+        };
+
+        let Some(options) = self.profiles.get(name) else {
+            let available = self.profiles.keys().cloned().collect::<Vec<_>>();
+            return Err(if available.is_empty() {
+                format!("unknown profile '{}', config file has no profiles", name)
+            } else {
+                format!("unknown profile '{}', available: {}", name, available.join(", "))
+            });
+        };
+
+        Ok(self.defaults.clone().overridden_by(options.clone()))
+    }
+}
+
+impl FileOptions {
+    // options set in other replace these ones
+    fn overridden_by(self, other: FileOptions) -> FileOptions {
+        // language and scheme are taken together with their files, so file never shadows chosen name
+        let (language, language_file) = if other.language.is_some() || other.language_file.is_some() {
+            (other.language, other.language_file)
+        } else {
+            (self.language, self.language_file)
+        };
+
+        let (scheme, scheme_file) = if other.scheme.is_some() || other.scheme_file.is_some() {
+            (other.scheme, other.scheme_file)
+        } else {
+            (self.scheme, self.scheme_file)
+        };
+
+        FileOptions {
+            mode: other.mode.or(self.mode),
+            book: other.book.or(self.book),
+            language,
+            language_file,
+            scheme,
+            scheme_file,
+            count: other.count.or(self.count),
+            top: other.top.or(self.top),
+            weighted: other.weighted.or(self.weighted),
+            length: other.length.or(self.length),
+            quote_id: other.quote_id.or(self.quote_id),
+            from_repo: other.from_repo.or(self.from_repo),
+            strip_comments: other.strip_comments.or(self.strip_comments),
+            format: other.format.or(self.format),
+            ascii: other.ascii.or(self.ascii),
+            time: other.time.or(self.time),
+            punctuation: other.punctuation.or(self.punctuation),
+            numbers: other.numbers.or(self.numbers),
+            strict: other.strict.or(self.strict),
+            death: other.death.or(self.death),
+            lenient_accents: other.lenient_accents.or(self.lenient_accents),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_overrides_defaults() {
+        let file: ConfigFile = toml::from_str(r#"
+            [defaults]
+            count = 25
+            language = "english"
+            punctuation = true
+
+            [profiles.code]
+            mode = "code"
+            language-file = "words.txt"
+        "#).unwrap();
+
+        let options = file.options(Some("code")).unwrap();
+        assert_eq!(options.mode.as_deref(), Some("code"));
+        assert_eq!(options.count, Some(25));
+        assert_eq!(options.punctuation, Some(true));
+        // file of profile replaces language of defaults
+        assert_eq!(options.language, None);
+        assert_eq!(options.language_file.as_deref(), Some("words.txt"));
+
+        assert_eq!(file.options(None).unwrap().language.as_deref(), Some("english"));
+        assert!(file.options(Some("missing")).unwrap_err().contains("available: code"));
+    }
+
+    #[test]
+    fn saved_file_loads_back() {
+        let path = std::env::temp_dir().join(format!("typecrab-config-{}", std::process::id())).join("config.toml");
+        assert!(ConfigFile::load(&path).unwrap().profiles.is_empty());
+
+        let mut file = ConfigFile::default();
+        file.profiles.insert("short".into(), FileOptions { time: Some(15), ..FileOptions::default() });
+        file.save(&path).unwrap();
+
+        assert_eq!(ConfigFile::load(&path).unwrap().options(Some("short")).unwrap().time, Some(15));
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -6,8 +6,18 @@ mod test;
 mod result;
 mod scheme;
 mod start;
+mod stats;
+mod settings;
 
-pub use scheme::load_scheme_file;
+pub use scheme::{
+    load_scheme_file,
+    load_scheme_css
+};
 pub use test::TestView;
 pub use result::ResultView;
 pub use start::StartView;
+pub use stats::StatsView;
+pub use settings::{
+    Settings,
+    SettingsView
+};
@@ -2,7 +2,6 @@
  * cli/src/tui/result.rs
  */
 
-use once_cell::sync::Lazy;
 use ratatui::{
     buffer::Buffer,
     layout::{
@@ -27,10 +26,16 @@ use ratatui::{
     },
 };
 use std::collections::HashSet;
-use core::results::FinalResults;
+use core::{
+    results::FinalResults,
+    date_string,
+    EndReason,
+    Quote
+};
 
 use crate::tui::scheme::{
     styled_block,
+    Themed,
     COLOR_LIGHT,
     COLOR_RED,
     COLOR_WHITE,
@@ -39,24 +44,30 @@ use crate::tui::scheme::{
 };
 
 // info block styles
-static STYLE_INFO_LABEL: Lazy<Style> = Lazy::new(|| Style::default().fg(*COLOR_WHITE));
-static STYLE_INFO_VALUE: Lazy<Style> = Lazy::new(|| Style::default().fg(*COLOR_ORANGE));
+static STYLE_INFO_LABEL: Themed<Style> = Themed::new(|| Style::default().fg(COLOR_WHITE.get()));
+static STYLE_INFO_VALUE: Themed<Style> = Themed::new(|| Style::default().fg(COLOR_ORANGE.get()));
 
 // errors block styles
-static STYLE_KEY_OK: Lazy<Style> = Lazy::new(|| Style::default().fg(*COLOR_ORANGE));
-static STYLE_KEY_ERR: Lazy<Style> = Lazy::new(|| Style::default().fg(*COLOR_RED));
+static STYLE_KEY_OK: Themed<Style> = Themed::new(|| Style::default().fg(COLOR_ORANGE.get()));
+static STYLE_KEY_ERR: Themed<Style> = Themed::new(|| Style::default().fg(COLOR_RED.get()));
 
 // graph block styles
-static STYLE_GRAPH_WPM: Lazy<Style> = Lazy::new(|| Style::default().fg(*COLOR_ORANGE));
-static STYLE_GRAPH_RAW: Lazy<Style> = Lazy::new(|| Style::default().fg(*COLOR_LIGHT));
-static STYLE_GRAPH_ERR: Lazy<Style> = Lazy::new(|| Style::default().fg(*COLOR_RED));
-static STYLE_GRAPH_BACKGROUND: Lazy<Style> = Lazy::new(|| Style::default().bg(*COLOR_DARK));
-static STYLE_GRAPH_AXIS: Lazy<Style> = Lazy::new(|| Style::default().fg(*COLOR_WHITE));
-static STYLE_GRAPH_LABEL: Lazy<Style> = Lazy::new(|| Style::default().fg(*COLOR_ORANGE));
+static STYLE_GRAPH_WPM: Themed<Style> = Themed::new(|| Style::default().fg(COLOR_ORANGE.get()));
+static STYLE_GRAPH_RAW: Themed<Style> = Themed::new(|| Style::default().fg(COLOR_LIGHT.get()));
+static STYLE_GRAPH_ERR: Themed<Style> = Themed::new(|| Style::default().fg(COLOR_RED.get()));
+static STYLE_GRAPH_BACKGROUND: Themed<Style> = Themed::new(|| Style::default().bg(COLOR_DARK.get()));
+static STYLE_GRAPH_AXIS: Themed<Style> = Themed::new(|| Style::default().fg(COLOR_WHITE.get()));
+static STYLE_GRAPH_LABEL: Themed<Style> = Themed::new(|| Style::default().fg(COLOR_ORANGE.get()));
 
 
+#[derive(Clone)]
 pub struct ResultView<'a> {
     pub results: &'a FinalResults,
+    pub end_reason: Option<EndReason>,
+    pub seed: Option<u64>,
+    pub daily: Option<u64>,     // day of daily challenge
+    pub quote: Option<&'a Quote>,
+    pub hints: &'a [(&'a str, &'a str)],    // available keys with their actions
 }
 
 impl<'a> Widget for ResultView<'a> {
@@ -75,8 +86,9 @@ impl<'a> Widget for ResultView<'a> {
             .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
             .split(parts_v[1]);
 
-        draw_info(self.results, parts_h[0], buf);
+        draw_info(&self, parts_h[0], buf);
         draw_keyboard(self.results, parts_h[1], buf);
+        draw_hints(self.hints, parts_v[1], buf);
     }
 }
 
@@ -84,10 +96,7 @@ fn draw_graph(results: &FinalResults, area: Rect, buf: &mut Buffer) {
     const TITLE: &str = " chart ";
 
     if results.graph_data.is_empty() {
-        Chart::default()
-            .block(styled_block(TITLE))
-            .style(*STYLE_GRAPH_BACKGROUND)
-            .render(area, buf);
+        draw_empty_chart(TITLE, area, buf);
         return;
     }
 
@@ -96,82 +105,108 @@ fn draw_graph(results: &FinalResults, area: Rect, buf: &mut Buffer) {
     let mut err_pts = Vec::<(f64, f64)>::new();
     let mut prev_incorrect = 0;
 
-    for (t, w, r, incorrect, ..) in &results.graph_data {
-        wpm_pts.push((*t, *w));
-        raw_pts.push((*t, *r));
-        if *incorrect > prev_incorrect {
-            err_pts.push((*t, *w));
+    for point in &results.graph_data {
+        wpm_pts.push((point.time, point.wpm));
+        raw_pts.push((point.time, point.raw_wpm));
+        if point.incorrect > prev_incorrect {
+            err_pts.push((point.time, point.wpm));
         }
-        prev_incorrect = *incorrect;
+        prev_incorrect = point.incorrect;
     }
 
     let datasets = vec![
         Dataset::default()
             .marker(Marker::Braille)
             .graph_type(GraphType::Line)
-            .style(*STYLE_GRAPH_RAW)
+            .style(STYLE_GRAPH_RAW.get())
             .data(&raw_pts),
         Dataset::default()
             .marker(Marker::Braille)
             .graph_type(GraphType::Line)
-            .style(*STYLE_GRAPH_WPM)
+            .style(STYLE_GRAPH_WPM.get())
             .data(&wpm_pts),
         Dataset::default()
             .marker(Marker::Dot)
             .graph_type(GraphType::Scatter)
-            .style(*STYLE_GRAPH_ERR)
+            .style(STYLE_GRAPH_ERR.get())
             .data(&err_pts),
     ];
 
     let x_max = results
         .graph_data
         .last()
-        .map(|(t, ..)| t.ceil().max(1.0))
+        .map(|point| point.time.ceil().max(1.0))
         .unwrap_or(1.0);
 
     let y_max = results.graph_data
         .iter()
-        .map(|&(_, w, r, _, _, _)| w.max(r))
-        .fold(0.0, f64::max)
-        .ceil()
-        .max(1.0);
+        .map(|point| point.wpm.max(point.raw_wpm))
+        .fold(0.0, f64::max);
+
+    draw_chart(TITLE, datasets, (x_max, "s"), (y_max, "wpm"), area, buf);
+}
+
+// empty chart block with graph background
+pub(crate) fn draw_empty_chart(title: &str, area: Rect, buf: &mut Buffer) {
+    Chart::default()
+        .block(styled_block(title))
+        .style(STYLE_GRAPH_BACKGROUND.get())
+        .render(area, buf);
+}
 
+// common chart setup: axes from zero, y rounded up to tens, labeled with given units
+pub(crate) fn draw_chart(
+    title: &str,
+    datasets: Vec<Dataset>,
+    (x_max, x_unit): (f64, &str),
+    (y_max, y_unit): (f64, &str),
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    let y_max = y_max.ceil().max(1.0);
     let y_top = ((y_max / 10.0).ceil() * 10.0) as u64;
 
+    // thin out x labels if they do not fit, keeping them evenly spaced
+    let max_labels = (area.width as u64 / 4).max(2);
+    let x_step = (x_max.ceil().max(1.0) as u64).div_ceil(max_labels - 1).max(1);
+    let x_max = ((x_max.ceil().max(1.0) as u64).div_ceil(x_step) * x_step) as f64;
+
     let x_labels: Vec<Line> = (0..=x_max as u64)
-        .map(|v| Line::styled(v.to_string(), *STYLE_GRAPH_LABEL))
+        .step_by(x_step as usize)
+        .map(|v| Line::styled(v.to_string(), STYLE_GRAPH_LABEL.get()))
         .collect();
 
     let y_labels: Vec<Line> = (0..=y_top)
         .step_by(10)
-        .map(|v| Line::styled(v.to_string(), *STYLE_GRAPH_LABEL))
+        .map(|v| Line::styled(v.to_string(), STYLE_GRAPH_LABEL.get()))
         .collect();
 
     Chart::new(datasets)
-        .block(styled_block(TITLE))
-        .style(*STYLE_GRAPH_BACKGROUND)
+        .block(styled_block(title))
+        .style(STYLE_GRAPH_BACKGROUND.get())
         .x_axis(
             Axis::default()
                 .bounds([0.0, x_max])
                 .labels(x_labels)
-                .title(Line::styled("s", *STYLE_GRAPH_LABEL))
-                .style(*STYLE_GRAPH_AXIS),
+                .title(Line::styled(x_unit, STYLE_GRAPH_LABEL.get()))
+                .style(STYLE_GRAPH_AXIS.get()),
         )
         .y_axis(
             Axis::default()
                 .bounds([0.0, y_top as f64])
                 .labels(y_labels)
-                .title(Line::styled("wpm", *STYLE_GRAPH_LABEL))
-                .style(*STYLE_GRAPH_AXIS),
+                .title(Line::styled(y_unit, STYLE_GRAPH_LABEL.get()))
+                .style(STYLE_GRAPH_AXIS.get()),
         )
         .render(area, buf);
 }
 
-fn draw_info(results: &FinalResults, area: Rect, buf: &mut Buffer) {
+fn draw_info(view: &ResultView, area: Rect, buf: &mut Buffer) {
+    let results = view.results;
     let k = &results.key_presses;
 
     // stat: value
-    let rows = [
+    let mut rows = vec![
         ("wpm ", results.wpm.round().to_string()),
         ("raw ", results.raw_wpm.round().to_string()),
         ("accuracy ", format!("{}%", results.accuracy.round() as u32)),
@@ -179,12 +214,34 @@ fn draw_info(results: &FinalResults, area: Rect, buf: &mut Buffer) {
         ("characters ", format!("{}/{}/{}/{}", k.correct, k.incorrect, k.extra, k.missed),),
     ];
 
+    // accuracy with accents ignored, only if accent mistakes make a difference
+    if results.lenient_accuracy.round() != results.accuracy.round() {
+        rows.insert(3, ("lenient ", format!("{}%", results.lenient_accuracy.round() as u32)));
+    }
+
+    // author and source, bare id if quote has no metadata
+    if let Some(quote) = view.quote {
+        rows.push(("quote ", quote.attribution().unwrap_or_else(|| quote.id.clone())));
+    }
+
+    if let Some(reason) = view.end_reason {
+        rows.push(("end ", reason.as_str().to_string()));
+    }
+
+    if let Some(seed) = view.seed {
+        rows.push(("seed ", seed.to_string()));
+    }
+
+    if let Some(day) = view.daily {
+        rows.push(("daily ", date_string(day)));
+    }
+
     let lines: Vec<Line> = rows
         .iter()
         .map(|(label, value)| {
             Line::from(vec![
-                Span::styled(*label, *STYLE_INFO_LABEL),
-                Span::styled(value.clone(), *STYLE_INFO_VALUE),
+                Span::styled(*label, STYLE_INFO_LABEL.get()),
+                Span::styled(value.clone(), STYLE_INFO_VALUE.get()),
             ])
         })
         .collect();
@@ -194,7 +251,47 @@ fn draw_info(results: &FinalResults, area: Rect, buf: &mut Buffer) {
         .render(area, buf);
 }
 
+// key hints over bottom border, aligned to the right
+fn draw_hints(hints: &[(&str, &str)], area: Rect, buf: &mut Buffer) {
+    if hints.is_empty() || area.height == 0 || area.width < 2 {
+        return;
+    }
+
+    let spans = hints
+        .iter()
+        .flat_map(|(key, action)| [
+            Span::styled(format!(" {} ", key), STYLE_INFO_VALUE.get()),
+            Span::styled(format!("{} ", action), STYLE_INFO_LABEL.get()),
+        ])
+        .collect::<Vec<_>>();
+
+    let line = Line::from(spans).right_aligned();
+    let width = (line.width() as u16).min(area.width - 2);
+    let line_area = Rect::new(area.right() - 1 - width, area.bottom() - 1, width, 1);
+
+    line.render(line_area, buf);
+}
+
 fn draw_keyboard(results: &FinalResults, area: Rect, buf: &mut Buffer) {
+    let error_keys: HashSet<char> = results
+        .errors
+        .iter()
+        .map(|(c, _)| c.to_ascii_uppercase())
+        .collect();
+
+    let key_style = |key: char| {
+        if error_keys.contains(&key) {
+            STYLE_KEY_ERR.get()
+        } else {
+            STYLE_KEY_OK.get()
+        }
+    };
+
+    draw_keys(" keystrokes ", key_style, area, buf);
+}
+
+// keyboard layout, every key is styled by given function of its (uppercase) character
+pub(crate) fn draw_keys<F: Fn(char) -> Style>(title: &str, key_style: F, area: Rect, buf: &mut Buffer) {
     const KEYS: [&[&str]; 4] = [
         &["`", "1", "2", "3", "4", "5", "6", "7", "8", "9", "0", "-", "="],
         &["Q", "W", "E", "R", "T", "Y", "U", "I", "O", "P", "[", "]", "\\"],
@@ -203,12 +300,6 @@ fn draw_keyboard(results: &FinalResults, area: Rect, buf: &mut Buffer) {
     ];
     const SHIFTS: [usize; 4] = [0, 2, 4, 6];
 
-    let error_keys: HashSet<char> = results
-        .errors
-        .iter()
-        .map(|(c, _)| c.to_ascii_uppercase())
-        .collect();
-
     // horizontal centering
     let row_lens: Vec<usize> = KEYS
         .iter()
@@ -237,14 +328,7 @@ fn draw_keyboard(results: &FinalResults, area: Rect, buf: &mut Buffer) {
     for (row_idx, row) in KEYS.iter().enumerate() {
         let key_spans: Vec<Span> = row
             .iter()
-            .map(|key| {
-                let style = if error_keys.contains(&key.chars().next().unwrap()) {
-                    *STYLE_KEY_ERR
-                } else {
-                    *STYLE_KEY_OK
-                };
-                Span::styled(format!(" {} ", key), style)
-            })
+            .map(|key| Span::styled(format!(" {} ", key), key_style(key.chars().next().unwrap())))
             .collect();
 
         let mut line_spans = Vec::<Span>::new();
@@ -259,7 +343,6 @@ fn draw_keyboard(results: &FinalResults, area: Rect, buf: &mut Buffer) {
     }
 
     Paragraph::new(lines)
-        .block(styled_block(" keystrokes "))
+        .block(styled_block(title))
         .render(area, buf);
 }
-
@@ -2,10 +2,6 @@
  * cli/src/tui/scheme.rs
  */
 
-use once_cell::sync::{
-    Lazy,
-    OnceCell,
-};
 use ratatui::{
     prelude::{
         Color,
@@ -21,18 +17,67 @@ use ratatui::{
 use regex::Regex;
 use std::collections::HashMap;
 use std::fs;
+use std::sync::{
+    atomic::{
+        AtomicUsize,
+        Ordering
+    },
+    RwLock,
+};
+
+// colors of loaded scheme, parsed once when it is loaded
+static SCHEME_COLORS: RwLock<Option<HashMap<String, Color>>> = RwLock::new(None);
+
+// number of loaded schemes, themed values made for older one are made again
+static SCHEME_GENERATION: AtomicUsize = AtomicUsize::new(0);
+
+// value derived from current scheme, cached until other scheme is loaded at runtime
+pub(crate) struct Themed<T> {
+    make: fn() -> T,
+    cached: RwLock<Option<(usize, T)>>,
+}
 
-static SCHEME_VARS: OnceCell<HashMap<String, String>> = OnceCell::new();
+impl<T: Copy> Themed<T> {
+    pub(crate) const fn new(make: fn() -> T) -> Self {
+        Self { make, cached: RwLock::new(None) }
+    }
+
+    pub(crate) fn get(&self) -> T {
+        let generation = SCHEME_GENERATION.load(Ordering::Acquire);
+        if let Ok(cached) = self.cached.read() {
+            if let Some((made_for, value)) = *cached {
+                if made_for == generation {
+                    return value;
+                }
+            }
+        }
+
+        let value = (self.make)();
+        if let Ok(mut cached) = self.cached.write() {
+            *cached = Some((generation, value));
+        }
+        value
+    }
+}
 
-// function to load scheme into hash map
+// function to load scheme file into color map, replaces previously loaded one
 pub fn load_scheme_file(path: &str) -> Result<(), String> {
     let content = fs::read_to_string(path)
         .map_err(|_e| format!("cannot read scheme file '{}'", path))?;
-    let map = parse_css_variables(&content)?;
+    load_scheme_css(&content)
+}
+
+// same for scheme contents, e.g. built-in one
+pub fn load_scheme_css(content: &str) -> Result<(), String> {
+    let colors = parse_css_variables(content)?
+        .into_iter()
+        .map(|(name, hex)| (name, parse_hex_color(&hex)))
+        .collect();
 
-    SCHEME_VARS
-        .set(map)
-        .map_err(|_| "scheme was already loaded".to_string())?;
+    *SCHEME_COLORS
+        .write()
+        .map_err(|_e| "scheme lock is poisoned".to_string())? = Some(colors);
+    SCHEME_GENERATION.fetch_add(1, Ordering::Release);
 
     Ok(())
 }
@@ -70,41 +115,69 @@ fn parse_hex_color(hex_str: &str) -> Color {
     Color::White
 }
 
-// helper to get color from hash map
+// linear blend between two colors, non rgb colors switch at the middle
+pub(crate) fn blend_color(from: Color, to: Color, t: f64) -> Color {
+    let t = t.clamp(0.0, 1.0);
+    match (from, to) {
+        (Color::Rgb(r1, g1, b1), Color::Rgb(r2, g2, b2)) => {
+            let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+            Color::Rgb(mix(r1, r2), mix(g1, g2), mix(b1, b2))
+        }
+        _ => if t < 0.5 { from } else { to },
+    }
+}
+
+// helper to get color from color map
 fn scheme_color(var_name: &str, fallback: Color) -> Color {
-    if let Some(map) = SCHEME_VARS.get() {
-        if let Some(hex) = map.get(var_name) {
-            return parse_hex_color(hex);
+    if let Ok(colors) = SCHEME_COLORS.read() {
+        if let Some(color) = colors.as_ref().and_then(|map| map.get(var_name)) {
+            return *color;
         }
     }
     fallback
 }
 
 // common colors
-pub static COLOR_RED: Lazy<Color> = Lazy::new(|| scheme_color("red-color", Color::Red));
-pub static COLOR_GREEN: Lazy<Color> = Lazy::new(|| scheme_color("green-color", Color::Green));
-pub static COLOR_YELLOW: Lazy<Color> = Lazy::new(|| scheme_color("yellow-color", Color::LightYellow));
-pub static COLOR_ORANGE: Lazy<Color> = Lazy::new(|| scheme_color("orange-color", Color::Yellow));
-pub static COLOR_WHITE: Lazy<Color> = Lazy::new(|| scheme_color("white-color", Color::White));
-pub static COLOR_DARK:  Lazy<Color> = Lazy::new(|| scheme_color("dark-color", Color::Black));
-pub static COLOR_LIGHT: Lazy<Color> = Lazy::new(|| scheme_color("light-color", Color::DarkGray));
+pub(crate) static COLOR_RED: Themed<Color> = Themed::new(|| scheme_color("red-color", Color::Red));
+pub(crate) static COLOR_GREEN: Themed<Color> = Themed::new(|| scheme_color("green-color", Color::Green));
+pub(crate) static COLOR_YELLOW: Themed<Color> = Themed::new(|| scheme_color("yellow-color", Color::LightYellow));
+pub(crate) static COLOR_ORANGE: Themed<Color> = Themed::new(|| scheme_color("orange-color", Color::Yellow));
+pub(crate) static COLOR_WHITE: Themed<Color> = Themed::new(|| scheme_color("white-color", Color::White));
+pub(crate) static COLOR_DARK:  Themed<Color> = Themed::new(|| scheme_color("dark-color", Color::Black));
+pub(crate) static COLOR_LIGHT: Themed<Color> = Themed::new(|| scheme_color("light-color", Color::DarkGray));
 
 // block styles
-pub static STYLE_BACKGROUND: Lazy<Style> = Lazy::new(|| Style::default().bg(*COLOR_DARK));
-pub static STYLE_BORDER: Lazy<Style> = Lazy::new(|| Style::default().fg(*COLOR_ORANGE));
-pub static STYLE_TITLE: Lazy<Style> = Lazy::new(|| Style::default().fg(*COLOR_WHITE));
+pub(crate) static STYLE_BACKGROUND: Themed<Style> = Themed::new(|| Style::default().bg(COLOR_DARK.get()));
+pub(crate) static STYLE_BORDER: Themed<Style> = Themed::new(|| Style::default().fg(COLOR_ORANGE.get()));
+pub(crate) static STYLE_TITLE: Themed<Style> = Themed::new(|| Style::default().fg(COLOR_WHITE.get()));
 
 
 pub(crate) fn styled_block(title: &str) -> Block<'_> {
     Block::default()
         // background
-        .style(*STYLE_BACKGROUND)
+        .style(STYLE_BACKGROUND.get())
 
         // title
-        .title(Span::styled(title, *STYLE_TITLE))
+        .title(Span::styled(title, STYLE_TITLE.get()))
 
         // border
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
-        .border_style(*STYLE_BORDER)
+        .border_style(STYLE_BORDER.get())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn switched_scheme_replaces_cached_styles() {
+        load_scheme_css(":root { --orange-color: #ff8800; }").unwrap();
+        assert_eq!(COLOR_ORANGE.get(), Color::Rgb(0xff, 0x88, 0x00));
+        assert_eq!(STYLE_BORDER.get().fg, Some(Color::Rgb(0xff, 0x88, 0x00)));
+
+        load_scheme_css(":root { --orange-color: #102030; }").unwrap();
+        assert_eq!(STYLE_BORDER.get().fg, Some(Color::Rgb(0x10, 0x20, 0x30)));
+        assert_eq!(COLOR_WHITE.get(), Color::White);
+    }
 }
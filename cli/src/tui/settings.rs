@@ -0,0 +1,347 @@
+/*
+ * cli/src/tui/settings.rs
+ */
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{
+        Constraint,
+        Direction,
+        Layout,
+        Rect
+    },
+    style::Style,
+    text::{
+        Line,
+        Span
+    },
+    widgets::{
+        Paragraph,
+        Widget
+    },
+};
+use core::{
+    is_code_pack,
+    list_quotes,
+    pack_names,
+    Config,
+    GameMode,
+    Language,
+    Level,
+    PackKind,
+    QuoteLength
+};
+
+use crate::tui::{
+    scheme::{
+        styled_block,
+        Themed,
+        COLOR_LIGHT,
+        COLOR_ORANGE,
+        COLOR_WHITE
+    },
+    test::build_status,
+};
+
+// field styles
+static STYLE_LABEL: Themed<Style> = Themed::new(|| Style::default().fg(COLOR_WHITE.get()));
+static STYLE_VALUE: Themed<Style> = Themed::new(|| Style::default().fg(COLOR_LIGHT.get()));
+static STYLE_SELECTED: Themed<Style> = Themed::new(|| Style::default().fg(COLOR_ORANGE.get()));
+
+// preset values, left and right arrows step through them
+const WORD_COUNTS: [usize; 5] = [10, 25, 50, 100, 250];
+const TIME_LIMITS: [Option<u32>; 5] = [None, Some(15), Some(30), Some(60), Some(120)];
+const QUOTE_LENGTHS: [Option<QuoteLength>; 5] = [
+    None,
+    Some(QuoteLength::Short),
+    Some(QuoteLength::Medium),
+    Some(QuoteLength::Long),
+    Some(QuoteLength::Thicc),
+];
+
+#[derive(Clone, Copy, PartialEq)]
+enum Field {
+    Mode,
+    Language,
+    Count,
+    Quote,
+    Length,
+    Time,
+    Punctuation,
+    Numbers,
+    Backtrack,
+    Death,
+    Accents,
+    Scheme,
+}
+
+impl Field {
+    fn label(&self) -> &'static str {
+        match self {
+            Field::Mode => "game mode",
+            Field::Language => "language",
+            Field::Count => "word count",
+            Field::Quote => "quote",
+            Field::Length => "quote length",
+            Field::Time => "time limit",
+            Field::Punctuation => "punctuation",
+            Field::Numbers => "numbers",
+            Field::Backtrack => "backtrack",
+            Field::Death => "death",
+            Field::Accents => "lenient accents",
+            Field::Scheme => "color scheme",
+        }
+    }
+}
+
+// config edited on settings screen, applied only after validation
+#[derive(Clone)]
+pub struct Settings {
+    pub config: Config,
+    pub scheme: String,
+    selected: usize,
+}
+
+impl Settings {
+    pub fn new(config: Config, scheme: String) -> Self {
+        Self { config, scheme, selected: 0 }
+    }
+
+    // fields used by current mode, validation ignores the rest
+    fn fields(&self) -> Vec<Field> {
+        match self.config.mode {
+            GameMode::Words => vec![
+                Field::Mode, Field::Language, Field::Count, Field::Time,
+                Field::Punctuation, Field::Numbers, Field::Backtrack, Field::Death, Field::Accents, Field::Scheme,
+            ],
+            // blocks of source tree have no ids
+            GameMode::Code if self.config.repo.is_some() => vec![
+                Field::Mode, Field::Language, Field::Length, Field::Time, Field::Backtrack, Field::Death, Field::Accents, Field::Scheme,
+            ],
+            GameMode::Quote | GameMode::Code => vec![
+                Field::Mode, Field::Language, Field::Quote, Field::Length, Field::Time, Field::Backtrack, Field::Death, Field::Accents, Field::Scheme,
+            ],
+            GameMode::Zen => vec![Field::Mode, Field::Scheme],
+            // book is chosen on command line, its file can only be left with mode change
+            GameMode::Book => vec![
+                Field::Mode, Field::Count, Field::Time, Field::Backtrack, Field::Death, Field::Accents, Field::Scheme,
+            ],
+        }
+    }
+
+    fn selected_field(&self) -> Field {
+        let fields = self.fields();
+        fields[self.selected.min(fields.len() - 1)]
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1).min(self.fields().len() - 1);
+    }
+
+    pub fn select_prev(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    // steps value of selected field forward or backward, booleans are toggled
+    pub fn change(&mut self, forward: bool) {
+        let field = self.selected_field();
+        let config = &mut self.config;
+
+        match field {
+            Field::Mode => {
+                const MODES: [GameMode; 4] = [GameMode::Words, GameMode::Quote, GameMode::Code, GameMode::Zen];
+                config.mode = step(&MODES, &config.mode, forward);
+                config.language = language_for(&config.language, config.mode);
+                config.file = None;
+                config.repo = None;
+                config.strip_comments = false;
+                config.text_format = None;
+                config.ascii = false;
+
+                // hidden values back to defaults, so validation has nothing to warn about
+                let defaults = Config::default();
+                if config.mode != GameMode::Words {
+                    config.word_count = defaults.word_count;
+                    config.punctuation = defaults.punctuation;
+                    config.numbers = defaults.numbers;
+                    config.top_words = defaults.top_words;
+                    config.weighted = defaults.weighted;
+                }
+                if !matches!(config.mode, GameMode::Quote | GameMode::Code) {
+                    config.quote_length = defaults.quote_length;
+                    config.quote_id = defaults.quote_id;
+                }
+                if config.mode == GameMode::Zen {
+                    config.time_limit = defaults.time_limit;
+                    config.backtrack = defaults.backtrack;
+                    config.death = defaults.death;
+                    config.lenient_accents = defaults.lenient_accents;
+                }
+            }
+            Field::Language => {
+                let languages = languages_of(config.mode);
+                config.language = step(&languages, &config.language, forward);
+                config.file = None;
+                config.repo = None;
+                config.strip_comments = false;
+                config.text_format = None;
+                config.ascii = false;
+                config.quote_id = None;
+            }
+            Field::Count => config.word_count = step_preset(&WORD_COUNTS, config.word_count, forward),
+            // chosen quote and length exclude each other
+            Field::Quote => {
+                let mut ids = vec![None];
+                ids.extend(list_quotes(&config.language).payload.into_iter().map(|quote| Some(quote.id)));
+                config.quote_id = step(&ids, &config.quote_id, forward);
+                if config.quote_id.is_some() {
+                    config.quote_length = None;
+                }
+            }
+            Field::Length => {
+                config.quote_length = step(&QUOTE_LENGTHS, &config.quote_length, forward);
+                if config.quote_length.is_some() {
+                    config.quote_id = None;
+                }
+            }
+            Field::Time => config.time_limit = step_preset(&TIME_LIMITS, config.time_limit, forward),
+            Field::Punctuation => config.punctuation = !config.punctuation,
+            Field::Numbers => config.numbers = !config.numbers,
+            Field::Backtrack => config.backtrack = !config.backtrack,
+            Field::Death => config.death = !config.death,
+            Field::Accents => config.lenient_accents = !config.lenient_accents,
+            Field::Scheme => {
+                self.scheme = step(&pack_names(PackKind::Schemes), &self.scheme, forward);
+            }
+        }
+
+        // mode change could hide selected field
+        self.selected = self.selected.min(self.fields().len() - 1);
+    }
+
+    fn value(&self, field: Field) -> String {
+        let config = &self.config;
+        let on_off = |value: bool| if value { "on" } else { "off" }.to_string();
+
+        match field {
+            Field::Mode => config.mode.as_str().to_string(),
+            Field::Language => match config.file.as_ref().or(config.repo.as_ref()) {
+                Some(path) => path.clone(),
+                None => config.language.as_str().to_string(),
+            },
+            Field::Count => config.word_count.to_string(),
+            Field::Quote => config.quote_id.clone().unwrap_or_else(|| "random".to_string()),
+            Field::Length => config.quote_length.map_or("any", |length| length.as_str()).to_string(),
+            Field::Time => config.time_limit.map_or("off".to_string(), |t| format!("{}s", t)),
+            Field::Punctuation => on_off(config.punctuation),
+            Field::Numbers => on_off(config.numbers),
+            Field::Backtrack => on_off(config.backtrack),
+            Field::Death => on_off(config.death),
+            Field::Accents => on_off(config.lenient_accents),
+            Field::Scheme => self.scheme.clone(),
+        }
+    }
+}
+
+// languages selectable in given mode
+fn languages_of(mode: GameMode) -> Vec<Language> {
+    match mode {
+        GameMode::Quote => pack_names(PackKind::Quotes).into_iter().map(Language::Quotes).collect(),
+        GameMode::Code => pack_names(PackKind::Quotes).into_iter().filter(|name| is_code_pack(name)).map(Language::Quotes).collect(),
+        GameMode::Words | GameMode::Zen | GameMode::Book => pack_names(PackKind::Words).into_iter().map(Language::Words).collect(),
+    }
+}
+
+// keeps language of the same name after mode change, otherwise first available one
+fn language_for(language: &Language, mode: GameMode) -> Language {
+    let languages = languages_of(mode);
+
+    languages
+        .iter()
+        .find(|l| l.as_str() == language.as_str())
+        .or(languages.first())
+        .cloned()
+        .unwrap_or_else(|| language.clone())
+}
+
+// next or previous item with wrap around, unknown current value starts from the first one
+fn step<T: Clone + PartialEq>(items: &[T], current: &T, forward: bool) -> T {
+    let len = items.len();
+    let index = match items.iter().position(|item| item == current) {
+        Some(i) if forward => (i + 1) % len,
+        Some(i) => (i + len - 1) % len,
+        None => 0,
+    };
+
+    items[index].clone()
+}
+
+// nearest preset above or below current value with wrap around, so custom values are kept in order
+fn step_preset<T: Copy + PartialOrd>(presets: &[T], current: T, forward: bool) -> T {
+    let found = if forward {
+        presets.iter().find(|&&p| p > current)
+    } else {
+        presets.iter().rev().find(|&&p| p < current)
+    };
+
+    match (found, forward) {
+        (Some(&p), _) => p,
+        (None, true) => presets[0],
+        (None, false) => presets[presets.len() - 1],
+    }
+}
+
+pub struct SettingsView<'a> {
+    pub settings: &'a Settings,
+    pub warning: Option<(Level, String)>,
+}
+
+impl<'a> Widget for SettingsView<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),    // fields
+                Constraint::Length(3), // status bar
+            ])
+            .split(area);
+
+        let settings = self.settings;
+        let selected = settings.selected_field();
+        let fields = settings.fields();
+        let label_width = fields.iter().map(|f| f.label().len()).max().unwrap_or(0) + 2;
+
+        // label, then value between arrows if selected
+        let lines: Vec<Line> = fields
+            .iter()
+            .map(|&field| {
+                let label = format!(" {:<width$}", field.label(), width = label_width);
+                let value = settings.value(field);
+
+                if field == selected {
+                    Line::from(vec![
+                        Span::styled(label, STYLE_SELECTED.get()),
+                        Span::styled(format!("< {} >", value), STYLE_SELECTED.get()),
+                    ])
+                } else {
+                    Line::from(vec![
+                        Span::styled(label, STYLE_LABEL.get()),
+                        Span::styled(format!("  {}", value), STYLE_VALUE.get()),
+                    ])
+                }
+            })
+            .collect();
+
+        Paragraph::new(lines)
+            .block(styled_block(" settings "))
+            .render(layout[0], buf);
+
+        // key hints, unless there is message to show
+        let hints = Some("↑↓ select  ←→ change  enter start  esc back".to_string());
+        let status_line = build_status(&self.warning, &hints);
+
+        Paragraph::new(status_line)
+            .block(styled_block(" status "))
+            .render(layout[1], buf);
+    }
+}
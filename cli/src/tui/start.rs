@@ -8,6 +8,7 @@ use ratatui::{
 
 use crate::tui::scheme::{COLOR_ORANGE, COLOR_WHITE, STYLE_BACKGROUND};
 
+#[derive(Clone)]
 pub struct StartView;
 
 impl Widget for StartView {
@@ -15,7 +16,7 @@ impl Widget for StartView {
         // background
         for y in area.top()..area.bottom() {
             for x in area.left()..area.right() {
-                buf[(x, y)].set_style(*STYLE_BACKGROUND);
+                buf[(x, y)].set_style(STYLE_BACKGROUND.get());
             }
         }
 
@@ -23,10 +24,7 @@ impl Widget for StartView {
         let lines = build_start();
 
         let height = lines.len() as u16;
-        let width = lines.iter().map(Line::width).max().unwrap_or(0) as u16;
-
         let y_offset = area.y + (area.height.saturating_sub(height)) / 2;
-        let x_offset = area.x + (area.width.saturating_sub(width)) / 2;
 
         for (i, line) in lines.into_iter().enumerate() {
             let y = y_offset + i as u16;
@@ -34,10 +32,12 @@ impl Widget for StartView {
                 break;
             }
 
-            let mut x = x_offset;
+            // every line centered on its own
+            let width = line.width() as u16;
+            let mut x = area.x + (area.width.saturating_sub(width)) / 2;
             for span in line.spans {
                 let content = span.content.clone();
-                let style = span.style.patch(*STYLE_BACKGROUND);
+                let style = span.style.patch(STYLE_BACKGROUND.get());
                 let width = span.width();
                 buf.set_string(x, y, content, style);
                 x += width as u16;
@@ -82,12 +82,26 @@ fn build_start() -> Vec<Line<'static>> {
         let text_idx = i.checked_sub(side_top_pad).unwrap_or(usize::MAX);
         let text = TEXT_LINES.get(text_idx).unwrap_or(&"");
 
-        let logo_span = Span::styled(*logo, Style::default().fg(*COLOR_ORANGE));
+        let logo_span = Span::styled(*logo, Style::default().fg(COLOR_ORANGE.get()));
         let gap_span = Span::raw("  ");
-        let text_span = Span::styled(*text, Style::default().fg(*COLOR_WHITE));
+        let text_span = Span::styled(*text, Style::default().fg(COLOR_WHITE.get()));
 
         lines.push(Line::from(vec![logo_span, gap_span, text_span]));
     }
-    
+
+    // key hints
+    const HINTS: &[(&str, &str)] = &[("any key", "start"), ("s", "settings"), ("esc", "quit")];
+
+    let hint_spans = HINTS
+        .iter()
+        .flat_map(|(key, action)| [
+            Span::styled(format!(" {} ", key), Style::default().fg(COLOR_ORANGE.get())),
+            Span::styled(format!("{} ", action), Style::default().fg(COLOR_WHITE.get())),
+        ])
+        .collect::<Vec<_>>();
+
+    lines.push(Line::default());
+    lines.push(Line::from(hint_spans));
+
     lines
 }
\ No newline at end of file
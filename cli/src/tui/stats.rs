@@ -0,0 +1,274 @@
+/*
+ * cli/src/tui/stats.rs
+ */
+
+use std::collections::HashMap;
+use ratatui::{
+    buffer::Buffer,
+    layout::{
+        Constraint,
+        Direction,
+        Layout,
+        Rect
+    },
+    style::Style,
+    symbols::Marker,
+    text::{
+        Line,
+        Span
+    },
+    widgets::{
+        Dataset,
+        GraphType,
+        Paragraph,
+        Widget
+    },
+};
+use core::{
+    GameMode,
+    HistoryRecord,
+    TestKind,
+    aggregate_errors,
+    average_over_period,
+    best_per_category,
+    daily_bests,
+    daily_streak,
+    date_string,
+    today,
+};
+
+use crate::tui::{
+    result::{
+        draw_chart,
+        draw_empty_chart,
+        draw_keys,
+    },
+    scheme::{
+        blend_color,
+        styled_block,
+        Themed,
+        COLOR_LIGHT,
+        COLOR_ORANGE,
+        COLOR_RED,
+        COLOR_WHITE,
+    },
+};
+
+// summary and bests block styles
+static STYLE_INFO_LABEL: Themed<Style> = Themed::new(|| Style::default().fg(COLOR_WHITE.get()));
+static STYLE_INFO_VALUE: Themed<Style> = Themed::new(|| Style::default().fg(COLOR_ORANGE.get()));
+
+// trend block styles
+static STYLE_TREND_WPM: Themed<Style> = Themed::new(|| Style::default().fg(COLOR_ORANGE.get()));
+static STYLE_TREND_ACC: Themed<Style> = Themed::new(|| Style::default().fg(COLOR_LIGHT.get()));
+
+
+pub struct StatsView<'a> {
+    pub records: &'a [HistoryRecord],
+}
+
+impl<'a> Widget for StatsView<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        // oldest session first, drills and practice on few chosen words would skew trend, best and averages
+        let mut records = self.records.iter().filter(|r| r.kind == TestKind::Regular).collect::<Vec<_>>();
+        records.sort_by_key(|r| r.timestamp);
+
+        // 60% for trend, 40% for down part
+        let parts_v = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(area);
+
+        draw_trend(&records, parts_v[0], buf);
+
+        // 25% for summary, 35% for bests, 40% for heatmap
+        let parts_h = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Percentage(25),
+                Constraint::Percentage(35),
+                Constraint::Percentage(40),
+            ])
+            .split(parts_v[1]);
+
+        draw_summary(self.records, &records, parts_h[0], buf);
+        draw_bests(self.records, parts_h[1], buf);
+        draw_heatmap(self.records, parts_h[2], buf);
+    }
+}
+
+// wpm and accuracy by session number
+fn draw_trend(records: &[&HistoryRecord], area: Rect, buf: &mut Buffer) {
+    const TITLE: &str = " trend ";
+
+    if records.is_empty() {
+        draw_empty_chart(TITLE, area, buf);
+        return;
+    }
+
+    let wpm_pts = records
+        .iter()
+        .enumerate()
+        .map(|(i, r)| ((i + 1) as f64, r.results.wpm))
+        .collect::<Vec<_>>();
+
+    let acc_pts = records
+        .iter()
+        .enumerate()
+        .map(|(i, r)| ((i + 1) as f64, r.results.accuracy))
+        .collect::<Vec<_>>();
+
+    let datasets = vec![
+        Dataset::default()
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(STYLE_TREND_ACC.get())
+            .data(&acc_pts),
+        Dataset::default()
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(STYLE_TREND_WPM.get())
+            .data(&wpm_pts),
+    ];
+
+    let y_max = wpm_pts
+        .iter()
+        .chain(acc_pts.iter())
+        .map(|&(_, v)| v)
+        .fold(0.0, f64::max);
+
+    draw_chart(TITLE, datasets, (records.len() as f64, "test"), (y_max, "wpm/%"), area, buf);
+}
+
+fn draw_summary(records: &[HistoryRecord], regular: &[&HistoryRecord], area: Rect, buf: &mut Buffer) {
+    let lines: Vec<Line> = summary_rows(records, regular, today())
+        .iter()
+        .map(|(label, value)| {
+            Line::from(vec![
+                Span::styled(*label, STYLE_INFO_LABEL.get()),
+                Span::styled(value.clone(), STYLE_INFO_VALUE.get()),
+            ])
+        })
+        .collect();
+
+    Paragraph::new(lines)
+        .block(styled_block(" summary "))
+        .render(area, buf);
+}
+
+// test count of all records, best and averages of regular tests, daily challenge streak
+fn summary_rows(records: &[HistoryRecord], regular: &[&HistoryRecord], today: u64) -> Vec<(&'static str, String)> {
+    let mut rows = vec![("tests ", records.len().to_string())];
+
+    if let Some(avg) = average_over_period(regular.iter().copied(), 0, u64::MAX) {
+        let best = regular.iter().map(|r| r.results.wpm).fold(0.0, f64::max);
+        rows.extend([
+            ("best ", best.round().to_string()),
+            ("wpm ", avg.wpm.round().to_string()),
+            ("raw ", avg.raw_wpm.round().to_string()),
+            ("accuracy ", format!("{}%", avg.accuracy.round() as u32)),
+            ("consistency ", format!("{}%", avg.consistency.round() as u32)),
+        ]);
+    }
+
+    // daily challenge streak and today's best attempt
+    let streak = daily_streak(records, today);
+    if streak > 0 {
+        rows.push(("streak ", format!("{} days", streak)));
+    }
+    if let Some(best) = daily_bests(records).first().filter(|r| r.daily == Some(today)) {
+        rows.push(("daily ", format!("{} wpm", best.results.wpm.round())));
+    }
+
+    rows
+}
+
+// personal bests per mode/language/word count
+fn draw_bests(records: &[HistoryRecord], area: Rect, buf: &mut Buffer) {
+    let mut bests = best_per_category(records);
+    bests.sort_by(|a, b| b.results.wpm.total_cmp(&a.results.wpm));
+
+    // recent daily challenges first, then regular tests
+    let mut lines: Vec<Line> = daily_bests(records)
+        .iter()
+        .take(3)
+        .filter_map(|r| r.daily.map(|day| (day, r)))
+        .map(|(day, r)| {
+            Line::from(vec![
+                Span::styled(format!("daily {} ", date_string(day)), STYLE_INFO_LABEL.get()),
+                Span::styled(
+                    format!("{} wpm {}%", r.results.wpm.round(), r.results.accuracy.round() as u32),
+                    STYLE_INFO_VALUE.get(),
+                ),
+            ])
+        })
+        .collect();
+
+    lines.extend(bests
+        .iter()
+        .map(|r| {
+            let label = match r.config.mode {
+                GameMode::Words => format!("{} {} {} ", r.config.mode.as_str(), r.config.language.as_str(), r.config.word_count),
+                _ => format!("{} {} ", r.config.mode.as_str(), r.config.language.as_str()),
+            };
+            Line::from(vec![
+                Span::styled(label, STYLE_INFO_LABEL.get()),
+                Span::styled(
+                    format!("{} wpm {}%", r.results.wpm.round(), r.results.accuracy.round() as u32),
+                    STYLE_INFO_VALUE.get(),
+                ),
+            ])
+        }));
+
+    Paragraph::new(lines)
+        .block(styled_block(" bests "))
+        .render(area, buf);
+}
+
+// keyboard colored by error count over all sessions
+fn draw_heatmap(records: &[HistoryRecord], area: Rect, buf: &mut Buffer) {
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    for (c, count) in aggregate_errors(records) {
+        *counts.entry(c.to_ascii_uppercase()).or_insert(0) += count;
+    }
+
+    let max = counts.values().copied().max().unwrap_or(0);
+
+    let key_style = |key: char| {
+        let count = counts.get(&key).copied().unwrap_or(0);
+        if count == 0 {
+            Style::default().fg(COLOR_ORANGE.get())
+        } else {
+            // even single mistake should stand out from clean keys
+            let heat = 0.3 + 0.7 * count as f64 / max as f64;
+            Style::default().fg(blend_color(COLOR_ORANGE.get(), COLOR_RED.get(), heat))
+        }
+    };
+
+    draw_keys(" mistakes ", key_style, area, buf);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::{
+        Config,
+        validate_config,
+        results::FinalResults
+    };
+
+    #[test]
+    fn drill_is_left_out_of_summary() {
+        let config = validate_config(Config::default()).payload;
+        let finished = |wpm, kind| HistoryRecord::new(config.clone(), FinalResults { wpm, accuracy: 95.0, ..FinalResults::default() }, Vec::new(), None).with_kind(kind);
+        let records = vec![finished(48.0, TestKind::Regular), finished(130.0, TestKind::Drill)];
+        let regular = records.iter().filter(|r| r.kind == TestKind::Regular).collect::<Vec<_>>();
+
+        let rows = summary_rows(&records, &regular, 0);
+        let value = |label| rows.iter().find(|(l, _)| *l == label).map(|(_, v)| v.as_str());
+
+        assert_eq!(value("tests "), Some("2"));
+        assert_eq!(value("best "), Some("48"));
+        assert_eq!(value("wpm "), Some("48"));
+    }
+}
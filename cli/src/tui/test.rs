@@ -23,13 +23,16 @@ use ratatui::{
         Widget,
     },
 };
-use once_cell::sync::Lazy;
 use unicode_width::UnicodeWidthStr;
 use core::Level;
 
-use core::Test;
+use core::{
+    GameMode,
+    Test
+};
 use crate::tui::scheme::{
     styled_block,
+    Themed,
     COLOR_GREEN,
     COLOR_YELLOW,
     COLOR_RED,
@@ -38,18 +41,18 @@ use crate::tui::scheme::{
 };
 
 // message level styles
-static STYLE_INFO: Lazy<Style> = Lazy::new(|| Style::default().fg(*COLOR_GREEN));
-static STYLE_WARNING: Lazy<Style> = Lazy::new(|| Style::default().fg(*COLOR_YELLOW));
-static STYLE_ERROR: Lazy<Style> = Lazy::new(|| Style::default().fg(*COLOR_RED));
+static STYLE_INFO: Themed<Style> = Themed::new(|| Style::default().fg(COLOR_GREEN.get()));
+static STYLE_WARNING: Themed<Style> = Themed::new(|| Style::default().fg(COLOR_YELLOW.get()));
+static STYLE_ERROR: Themed<Style> = Themed::new(|| Style::default().fg(COLOR_RED.get()));
 
 // input feedback styles
-static STYLE_CORRECT: Lazy<Style> = Lazy::new(|| Style::default().fg(*COLOR_GREEN));
-static STYLE_INCORRECT: Lazy<Style> = Lazy::new(|| Style::default().fg(*COLOR_RED));
+static STYLE_CORRECT: Themed<Style> = Themed::new(|| Style::default().fg(COLOR_GREEN.get()));
+static STYLE_INCORRECT: Themed<Style> = Themed::new(|| Style::default().fg(COLOR_RED.get()));
 
 // current character style
-static STYLE_ACTIVE: Lazy<Style> = Lazy::new(|| Style::default().fg(*COLOR_WHITE));
-static STYLE_UNDERLINE: Lazy<Style> = Lazy::new(|| Style::default().fg(*COLOR_WHITE).add_modifier(Modifier::UNDERLINED));
-static STYLE_INACTIVE: Lazy<Style> = Lazy::new(|| Style::default().fg(*COLOR_LIGHT));
+static STYLE_ACTIVE: Themed<Style> = Themed::new(|| Style::default().fg(COLOR_WHITE.get()));
+static STYLE_UNDERLINE: Themed<Style> = Themed::new(|| Style::default().fg(COLOR_WHITE.get()).add_modifier(Modifier::UNDERLINED));
+static STYLE_INACTIVE: Themed<Style> = Themed::new(|| Style::default().fg(COLOR_LIGHT.get()));
 
 pub struct TestView<'a> {
     pub test: &'a Test,
@@ -68,10 +71,15 @@ impl<'a> Widget for TestView<'a> {
             .split(area);
 
         // build test lines
-        let test_lines = build_test(self.test, layout[0].width as usize);
+        let (test_lines, current_line) = build_test(self.test, layout[0].width as usize);
+
+        // scroll to keep current and next lines visible, e.g. for streamed words
+        let height = layout[0].height.saturating_sub(2) as usize;
+        let scroll = (current_line + 2).saturating_sub(height);
 
         // render test
         let prompt = Paragraph::new(test_lines)
+            .scroll((scroll as u16, 0))
             .block(styled_block(" test "));
         prompt.render(layout[0], buf);
 
@@ -83,10 +91,16 @@ impl<'a> Widget for TestView<'a> {
     }
 }
 
-fn build_test(test: &Test, max_width: usize) -> Vec<Line> {
+// returns test lines and index of line with current word
+fn build_test(test: &Test, max_width: usize) -> (Vec<Line<'_>>, usize) {
+    if test.mode == GameMode::Code {
+        return build_code(test, max_width);
+    }
+
     let mut lines = Vec::new();
     let mut current_spans = Vec::new();
     let mut current_width = 0;
+    let mut current_line = 0;
 
     for (i, word) in test.words.iter().enumerate() {
 
@@ -115,13 +129,17 @@ fn build_test(test: &Test, max_width: usize) -> Vec<Line> {
             };
 
             if underline_space {
-                current_spans.push(Span::styled(" ", *STYLE_UNDERLINE));
+                current_spans.push(Span::styled(" ", STYLE_UNDERLINE.get()));
             } else {
                 current_spans.push(Span::raw(" "));
             }
             current_width += 1;
         }
 
+        if i == test.current_word {
+            current_line = lines.len();
+        }
+
         // add spans
         current_spans.extend(spans_for_word);
         current_width += word_width;
@@ -139,7 +157,76 @@ fn build_test(test: &Test, max_width: usize) -> Vec<Line> {
         lines.push(Line::from(current_spans));
     }
 
-    lines
+    (lines, current_line)
+}
+
+// code keeps its lines, indentation and blank lines, long lines are wrapped
+fn build_code(test: &Test, max_width: usize) -> (Vec<Line<'static>>, usize) {
+    let mut lines = Vec::new();
+    let mut current_spans: Vec<Span> = Vec::new();
+    let mut current_width = 0;
+    let mut current_line = 0;
+    let mut line_start = true;
+
+    for i in 0..test.words.len() {
+        let spans_for_word = word_to_spans(i, test);
+        let word_width = spans_for_word
+            .iter()
+            .map(|sp| code_width(&sp.content.replace('\n', "")))
+            .sum::<usize>();
+
+        // tokens of line are separated by space, indentation is not
+        let separated = !line_start && !test.words[i - 1].is_indent();
+
+        if !current_spans.is_empty() && current_width + word_width + usize::from(separated) > max_width - 2 {
+            lines.push(Line::from(std::mem::take(&mut current_spans)));
+            current_width = 0;
+        } else if separated {
+            // avoid duplicate underline at end
+            let prev = i - 1;
+            let underline_space = prev == test.current_word && test.words[prev].progress.len() >= test.words[prev].text.len();
+
+            current_spans.push(Span::styled(" ", if underline_space { STYLE_UNDERLINE.get() } else { Style::default() }));
+            current_width += 1;
+        }
+
+        if i == test.current_word {
+            current_line = lines.len();
+        }
+
+        line_start = false;
+        for span in spans_for_word {
+            // caret at line break
+            if span.content == "\n" && span.style == STYLE_UNDERLINE.get() {
+                current_spans.push(Span::styled(" ", span.style));
+            }
+
+            for (n, part) in span.content.split('\n').enumerate() {
+                if n > 0 {
+                    lines.push(Line::from(std::mem::take(&mut current_spans)));
+                    current_width = 0;
+                    line_start = true;
+                }
+                if !part.is_empty() {
+                    current_width += code_width(part);
+                    current_spans.push(Span::styled(part.replace('\t', TAB), span.style));
+                }
+            }
+        }
+    }
+
+    if !current_spans.is_empty() {
+        lines.push(Line::from(current_spans));
+    }
+
+    (lines, current_line)
+}
+
+// tab is shown as spaces
+const TAB: &str = "    ";
+
+fn code_width(text: &str) -> usize {
+    UnicodeWidthStr::width(text) + text.matches('\t').count() * TAB.len()
 }
 
 // if i > current_word, then grey (inactive word)
@@ -148,7 +235,7 @@ fn word_to_spans(i: usize, test: &Test) -> Vec<Span<'static>> {
     if i > test.current_word {
         return vec![Span::styled(
             test.words[i].text.clone(),
-            *STYLE_INACTIVE,
+            STYLE_INACTIVE.get(),
         )];
     }
 
@@ -179,63 +266,64 @@ fn highlight_word(typed: &str, text:  &str, is_current: bool, is_last: bool, ) -
         let r = text_chars[i];
 
         if mismatch {
-            spans.push(Span::styled(r.to_string(), *STYLE_INCORRECT));
+            spans.push(Span::styled(r.to_string(), STYLE_INCORRECT.get()));
         } else if t == r {
-            spans.push(Span::styled(t.to_string(), *STYLE_CORRECT));
+            spans.push(Span::styled(t.to_string(), STYLE_CORRECT.get()));
         } else {
             mismatch = true;
-            spans.push(Span::styled(r.to_string(), *STYLE_INCORRECT));
+            spans.push(Span::styled(r.to_string(), STYLE_INCORRECT.get()));
         }
         i += 1;
     }
 
-    // extra characters
+    // extra characters, line break typed in code is shown by sign
     for &c in &typed_chars[i..] {
-        spans.push(Span::styled(c.to_string(), *STYLE_INCORRECT));
+        let c = if c == '\n' { '↵' } else { c };
+        spans.push(Span::styled(c.to_string(), STYLE_INCORRECT.get()));
     }
 
     if is_current {
         if i < text_chars.len() {
             // underline next expected character
-            spans.push(Span::styled(text_chars[i].to_string(), *STYLE_UNDERLINE));
+            spans.push(Span::styled(text_chars[i].to_string(), STYLE_UNDERLINE.get()));
 
             // show remaining characters as inactive
             for &ch in &text_chars[i + 1..] {
-                spans.push(Span::styled(ch.to_string(), *STYLE_INACTIVE));
+                spans.push(Span::styled(ch.to_string(), STYLE_INACTIVE.get()));
             }
         } else if is_last {
             // underline space if at the end of the last word
-            spans.push(Span::styled(" ", *STYLE_UNDERLINE));
+            spans.push(Span::styled(" ", STYLE_UNDERLINE.get()));
         }
     } else if i < text_chars.len() {
         // inactive characters for incomplete word
         for &ch in &text_chars[i..] {
-            spans.push(Span::styled(ch.to_string(), *STYLE_INACTIVE));
+            spans.push(Span::styled(ch.to_string(), STYLE_INACTIVE.get()));
         }
     }
 
     spans
 }
 
-fn build_status(warning: &Option<(Level, String)>, status: &Option<String>) -> Line<'static> {
+pub(crate) fn build_status(warning: &Option<(Level, String)>, status: &Option<String>) -> Line<'static> {
 
     // priority - warning message
     if let Some((level, warning)) = warning {
         let (label, style) = match level {
-            Level::Info => ("info", *STYLE_INFO),
-            Level::Warning => ("warning", *STYLE_WARNING),
-            Level::Error => ("error", *STYLE_ERROR),
+            Level::Info => ("info", STYLE_INFO.get()),
+            Level::Warning => ("warning", STYLE_WARNING.get()),
+            Level::Error => ("error", STYLE_ERROR.get()),
         };
 
         return Line::from(vec![
             Span::styled(format!("{label}: "), style.clone()),
-            Span::styled(warning.clone(), *STYLE_ACTIVE),
+            Span::styled(warning.clone(), STYLE_ACTIVE.get()),
         ]);
     }
 
     // else status - words or time
     if let Some(status) = status {
-        return Line::from(Span::styled(status.clone(), *STYLE_ACTIVE));
+        return Line::from(Span::styled(status.clone(), STYLE_ACTIVE.get()));
     }
 
     // empty
@@ -3,6 +3,7 @@ use std::io::{self, Write};
 use std::path::Path;
 
 // Define constants for directory paths
+const RESOURCES_DIR: &str = "../resources";
 const QUOTES_DIR: &str = "../resources/quotes";
 const WORDS_DIR: &str = "../resources/words";
 const SCHEMES_DIR: &str = "../resources/schemes"; // New constant for schemes
@@ -24,19 +25,6 @@ fn read_base_path_from_env() -> io::Result<String> {
     Err(io::Error::new(io::ErrorKind::NotFound, "BASE_PATH not found in .env"))
 }
 
-// Function to convert kebab-case to CamelCase for enum variants
-fn kebab_to_camel(s: &str) -> String {
-    s.split('-')
-        .map(|word| {
-            let mut chars = word.chars();
-            match chars.next() {
-                None => String::new(),
-                Some(f) => f.to_uppercase().collect::<String>() + chars.as_str(),
-            }
-        })
-        .collect()
-}
-
 // Function to recursively copy a directory and its contents
 fn copy_dir_recursive(src: &Path, dst: &Path, ignore_folders: &[&str]) -> io::Result<()> {
     if !dst.exists() {
@@ -62,218 +50,97 @@ fn copy_dir_recursive(src: &Path, dst: &Path, ignore_folders: &[&str]) -> io::Re
     Ok(())
 }
 
-// Function to generate languages.rs file with enums
-fn generate_languages_file(base_path: &str) -> io::Result<()> {
-    let mut words_variants = Vec::new();
-    let words_path = Path::new(WORDS_DIR);
-
-    // Collect variants for WordsLanguages from .txt files
-    for entry in fs::read_dir(words_path)? {
-        let entry = entry?;
-        let path = entry.path();
-        if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("txt") {
-            if let Some(file_name) = path.file_name().and_then(|s| s.to_str()) {
-                let variant_name = kebab_to_camel(file_name.strip_suffix(".txt").unwrap_or(file_name));
-                words_variants.push((variant_name, file_name.to_string()));
-            }
-        }
-    }
-
-    let mut quotes_variants = Vec::new();
-    let quotes_path = Path::new(QUOTES_DIR);
+// Function to generate builtin.rs file with catalog of built-in packs
+fn generate_builtin_file(base_path: &str) -> io::Result<()> {
+    // Collect word lists from .txt files
+    let mut words = files_with_extension(Path::new(WORDS_DIR), "txt")?;
+    words.sort();
 
-    // Collect variants and quote files for QuotesLanguages from folders
-    for entry in fs::read_dir(quotes_path)? {
-        let entry = entry?;
-        let path = entry.path();
+    // Collect quote languages from folders, every one with its .txt files
+    let mut quotes = Vec::new();
+    for entry in fs::read_dir(QUOTES_DIR)? {
+        let path = entry?.path();
         if path.is_dir() {
             if let Some(folder_name) = path.file_name().and_then(|s| s.to_str()) {
-                let variant_name = kebab_to_camel(folder_name);
-                // Collect .txt files in the language directory
-                let mut quote_files = Vec::new();
-                for quote_entry in fs::read_dir(&path)? {
-                    let quote_entry = quote_entry?;
-                    let quote_path = quote_entry.path();
-                    if quote_path.is_file()
-                        && quote_path.extension().and_then(|s| s.to_str()) == Some("txt")
-                    {
-                        if let Some(quote_file_name) = quote_path.file_name().and_then(|s| s.to_str())
-                        {
-                            quote_files.push(quote_file_name.to_string());
-                        }
-                    }
-                }
+                let mut quote_files = files_with_extension(&path, "txt")?
+                    .into_iter()
+                    .map(|stem| format!("{}.txt", stem))
+                    .collect::<Vec<_>>();
                 quote_files.sort(); // Ensure consistent order
-                quotes_variants.push((variant_name, folder_name.to_string(), quote_files));
+                quotes.push((folder_name.to_string(), quote_files));
             }
         }
     }
+    quotes.sort();
 
-    // Collect variants for Schemes from .css files
-    let mut schemes_variants = Vec::new();
-    let schemes_path = Path::new(SCHEMES_DIR);
+    // Collect schemes from .css files
+    let mut schemes = files_with_extension(Path::new(SCHEMES_DIR), "css")?;
+    schemes.sort();
 
-    for entry in fs::read_dir(schemes_path)? {
-        let entry = entry?;
-        let path = entry.path();
-        if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("css") {
-            if let Some(file_name) = path.file_name().and_then(|s| s.to_str()) {
-                let variant_name = kebab_to_camel(file_name.strip_suffix(".css").unwrap_or(file_name));
-                schemes_variants.push((variant_name, file_name.to_string()));
-            }
-        }
-    }
-
-    // Generate languages.rs content
+    // Generate builtin.rs content
     let mut content = String::new();
 
-    content.push_str(&format!("pub const BASE_PATH: &str = \"{}\";\n\n", base_path));
-    content.push_str("use crate::config::{GameMode, Language};\n\n");
-    content.push_str("use serde::{Serialize, Deserialize};\n\n");
-
-    // WordsLanguages enum
-    content.push_str("/// Auto-generated enum for words languages\n");
-    content.push_str("#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]\npub enum WordsLanguages {\n");
-    for (variant_name, _) in &words_variants {
-        content.push_str(&format!("    {},\n", variant_name));
-    }
-    content.push_str("}\n\n");
-
-    content.push_str("impl WordsLanguages {\n");
-    content.push_str("    pub fn file_path(&self) -> &'static str { match self {\n");
-    for (variant_name, file_name) in &words_variants {
-        content.push_str(&format!(
-            "        WordsLanguages::{} => \"/assets/words/{}\",\n",
-            variant_name, file_name
-        ));
-    }
-    content.push_str("    } }\n\n");
-
-    content.push_str("    pub fn all() -> &'static [WordsLanguages] { &[\n");
-    for (variant_name, _) in &words_variants {
-        content.push_str(&format!("        WordsLanguages::{},\n", variant_name));
-    }
-    content.push_str("    ] }\n\n");
-
-    content.push_str("    pub fn as_str(&self) -> &'static str { match self {\n");
-    for (variant_name, file_name) in &words_variants {
-        let file_name_no_ext = file_name.strip_suffix(".txt").unwrap_or(file_name);
-        content.push_str(&format!(
-            "        WordsLanguages::{} => \"{}\",\n",
-            variant_name, file_name_no_ext
-        ));
-    }
-    content.push_str("    } }\n");
-    content.push_str("}\n\n");
-
-    // QuotesLanguages enum
-    content.push_str("/// Auto-generated enum for quotes languages\n");
-    content.push_str("#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]\npub enum QuotesLanguages {\n");
-    for (variant_name, _, _) in &quotes_variants {
-        content.push_str(&format!("    {},\n", variant_name));
-    }
-    content.push_str("}\n\n");
+    content.push_str("#[cfg(target_arch = \"wasm32\")]\n");
+    content.push_str(&format!("pub const BASE_PATH: &str = {:?};\n\n", base_path));
 
-    content.push_str("impl QuotesLanguages {\n");
-    content.push_str("    pub fn dir_path(&self) -> &'static str { match self {\n");
-    for (variant_name, folder_name, _) in &quotes_variants {
-        content.push_str(&format!(
-            "        QuotesLanguages::{} => \"/assets/quotes/{}/\",\n",
-            variant_name, folder_name
-        ));
+    content.push_str("pub(crate) const BUILTIN_WORDS: &[&str] = &[\n");
+    for name in &words {
+        content.push_str(&format!("    {:?},\n", name));
     }
-    content.push_str("    } }\n\n");
+    content.push_str("];\n\n");
 
-    content.push_str("    pub fn quote_files(&self) -> &'static [&'static str] { match self {\n");
-    for (variant_name, _, quote_files) in &quotes_variants {
-        content.push_str(&format!("        QuotesLanguages::{} => &[\n", variant_name));
-        for file_name in quote_files {
-            content.push_str(&format!("            \"{}\",\n", file_name));
-        }
-        content.push_str("        ],\n");
+    content.push_str("pub(crate) const BUILTIN_QUOTES: &[(&str, &[&str])] = &[\n");
+    for (name, quote_files) in &quotes {
+        content.push_str(&format!("    ({:?}, &{:?}),\n", name, quote_files));
     }
-    content.push_str("    } }\n\n");
+    content.push_str("];\n\n");
 
-    content.push_str("    pub fn all() -> &'static [QuotesLanguages] { &[\n");
-    for (variant_name, _, _) in &quotes_variants {
-        content.push_str(&format!("        QuotesLanguages::{},\n", variant_name));
+    content.push_str("pub(crate) const BUILTIN_SCHEMES: &[&str] = &[\n");
+    for name in &schemes {
+        content.push_str(&format!("    {:?},\n", name));
     }
-    content.push_str("    ] }\n\n");
+    content.push_str("];\n\n");
 
-    content.push_str("    pub fn as_str(&self) -> &'static str { match self {\n");
-    for (variant_name, folder_name, _) in &quotes_variants {
-        content.push_str(&format!(
-            "        QuotesLanguages::{} => \"{}\",\n",
-            variant_name, folder_name
-        ));
+    // Embedded contents for native builds, keyed by path relative to resources, web fetches them instead
+    let mut files = Vec::new();
+    files.extend(words.iter().map(|name| format!("words/{}.txt", name)));
+    for (name, quote_files) in &quotes {
+        files.extend(quote_files.iter().map(|file| format!("quotes/{}/{}", name, file)));
     }
-    content.push_str("    } }\n");
-    content.push_str("}\n\n");
+    files.extend(schemes.iter().map(|name| format!("schemes/{}.css", name)));
 
-    // Schemes enum
-    content.push_str("/// Auto-generated enum for color schemes\n");
-    content.push_str("#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]\npub enum Schemes {\n");
-    for (variant_name, _) in &schemes_variants {
-        content.push_str(&format!("    {},\n", variant_name));
-    }
-    content.push_str("}\n\n");
-
-    content.push_str("impl Schemes {\n");
-    content.push_str("    pub fn file_path(&self) -> &'static str { match self {\n");
-    for (variant_name, file_name) in &schemes_variants {
-        content.push_str(&format!(
-            "        Schemes::{} => \"/schemes/{}\",\n",
-            variant_name, file_name
-        ));
-    }
-    content.push_str("    } }\n\n");
+    let resources = fs::canonicalize(RESOURCES_DIR)?;
 
-    content.push_str("    pub fn all() -> &'static [Schemes] { &[\n");
-    for (variant_name, _) in &schemes_variants {
-        content.push_str(&format!("        Schemes::{},\n", variant_name));
+    content.push_str("#[cfg(not(target_arch = \"wasm32\"))]\n");
+    content.push_str("pub(crate) const BUILTIN_FILES: &[(&str, &str)] = &[\n");
+    for file in &files {
+        let path = resources.join(file);
+        content.push_str(&format!("    ({:?}, include_str!({:?})),\n", file, path.display().to_string()));
     }
-    content.push_str("    ] }\n\n");
+    content.push_str("];\n");
 
-    content.push_str("    pub fn as_str(&self) -> &'static str { match self {\n");
-    for (variant_name, file_name) in &schemes_variants {
-        let file_name_no_ext = file_name.strip_suffix(".css").unwrap_or(file_name);
-        content.push_str(&format!(
-            "        Schemes::{} => \"{}\",\n",
-            variant_name, file_name_no_ext
-        ));
-    }
-    content.push_str("    } }\n");
-    content.push_str("}\n\n");
-
-    // language_from_str function (unchanged)
-    content.push_str("/// Converts a language string and game mode to a Language enum variant.\n");
-    content.push_str("/// Defaults to Language::Words(WordsLanguages::En) if no match is found.\n");
-    content.push_str("pub fn language_from_str(lang: &str, mode: GameMode) -> Language {\n");
-    content.push_str("    match mode {\n");
-    content.push_str("        GameMode::Quote => {\n");
-    content.push_str("            QuotesLanguages::all()\n");
-    content.push_str("                .iter()\n");
-    content.push_str("                .find(|&l| l.as_str() == lang)\n");
-    content.push_str("                .map(|&l| Language::Quotes(l))\n");
-    content.push_str("                .unwrap_or(Language::Words(WordsLanguages::En))\n");
-    content.push_str("        }\n");
-    content.push_str("        GameMode::Words | GameMode::Zen => {\n");
-    content.push_str("            WordsLanguages::all()\n");
-    content.push_str("                .iter()\n");
-    content.push_str("                .find(|&l| l.as_str() == lang)\n");
-    content.push_str("                .map(|&l| Language::Words(l))\n");
-    content.push_str("                .unwrap_or(Language::Words(WordsLanguages::En))\n");
-    content.push_str("        }\n");
-    content.push_str("    }\n");
-    content.push_str("}\n");
-
-    // Write to languages.rs
-    let mut file = File::create("./src/languages.rs")?;
+    // Write to OUT_DIR, it is included by registry.rs
+    let out_dir = std::env::var("OUT_DIR").expect("OUT_DIR not set");
+    let mut file = File::create(Path::new(&out_dir).join("builtin.rs"))?;
     file.write_all(content.as_bytes())?;
 
     Ok(())
 }
 
+// Function to collect file stems with given extension in directory
+fn files_with_extension(dir: &Path, extension: &str) -> io::Result<Vec<String>> {
+    let mut stems = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some(extension) {
+            if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+                stems.push(stem.to_string());
+            }
+        }
+    }
+    Ok(stems)
+}
+
 fn main() -> io::Result<()> {
     let target = std::env::var("TARGET").expect("TARGET not set");
 
@@ -289,8 +156,13 @@ fn main() -> io::Result<()> {
     println!("Directory copied successfully!");
 
     let base_path = read_base_path_from_env()?;
-    generate_languages_file(&base_path)?;
-    println!("languages.rs generated successfully!");
+    generate_builtin_file(&base_path)?;
+    println!("builtin.rs generated successfully!");
+
+    // Catalog changes only with resources or web config
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=../resources");
+    println!("cargo:rerun-if-changed=../web/.env");
 
     Ok(())
 }
@@ -0,0 +1,250 @@
+/*
+ * core/src/book.rs
+ */
+
+use serde::{
+    Serialize,
+    Deserialize
+};
+
+use crate::{
+    daily::fnv_hash,
+    response::Response
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::PathBuf,
+};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{
+    config::{
+        Config,
+        GameMode
+    },
+    generator::load_text
+};
+
+pub type PassageResponse = Response<Option<Passage>>;
+
+// position in book, hash of its text tells books apart wherever the file is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub hash: u64,
+    pub offset: usize,      // words before passage
+}
+
+// part of book typed as one test
+#[derive(Debug, Clone, PartialEq)]
+pub struct Passage {
+    pub words: Vec<String>,
+    pub start: Bookmark,
+    pub total: usize,       // words in whole book
+}
+
+impl Passage {
+    // where reading continues after n typed words of passage
+    pub fn bookmark_after(&self, typed: usize) -> Bookmark {
+        Bookmark {
+            hash: self.start.hash,
+            offset: self.start.offset + typed.min(self.words.len()),
+        }
+    }
+
+    // following passage, after whole passage is typed
+    pub fn next(&self) -> Bookmark {
+        self.bookmark_after(self.words.len())
+    }
+
+    // percent of book read after n typed words of passage
+    pub fn progress(&self, typed: usize) -> f64 {
+        if self.total == 0 {
+            return 100.0;
+        }
+        self.bookmark_after(typed).offset as f64 / self.total as f64 * 100.0
+    }
+}
+
+// api function, that returns hash of book text for its bookmark, line endings do not matter
+pub fn book_hash(text: &str) -> u64 {
+    fnv_hash(text.replace("\r\n", "\n").as_bytes())
+}
+
+// book words, lines of paragraph are joined, paragraphs end with line break
+fn book_words(text: &str) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+
+    for paragraph in text.split("\n\n").filter(|p| !p.trim().is_empty()) {
+        if let Some(last) = words.last_mut() {
+            last.push('\n');
+        }
+        words.extend(paragraph.split_whitespace().map(|w| w.to_string()));
+    }
+
+    words
+}
+
+// api function, that cuts passage of n words at offset, book starts over after its end
+pub fn passage_at(text: &str, offset: usize, size: usize) -> Passage {
+    let words = book_words(&text.replace("\r\n", "\n"));
+    let total = words.len();
+    let offset = if offset < total { offset } else { 0 };
+
+    let mut passage = words[offset..total.min(offset + size)].to_vec();
+
+    // test ends with last word, not with line break
+    if let Some(last) = passage.last_mut() {
+        if last.ends_with('\n') {
+            last.pop();
+        }
+    }
+
+    Passage {
+        words: passage,
+        start: Bookmark { hash: book_hash(text), offset },
+        total,
+    }
+}
+
+// json file with offset of every started book, e.g. ~/.local/share/typecrab/bookmarks.json
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone)]
+pub struct BookmarkStore {
+    path: PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl BookmarkStore {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn open_default() -> Option<Self> {
+        crate::paths::data_dir().map(|dir| Self::new(dir.join("bookmarks.json")))
+    }
+
+    // offsets by hash in hex, json has only string keys
+    fn read(&self) -> Result<BTreeMap<String, usize>, String> {
+        if !self.path.exists() {
+            return Ok(BTreeMap::new());
+        }
+
+        let content = fs::read_to_string(&self.path)
+            .map_err(|e| format!("cannot read bookmarks '{}', {}", self.path.display(), e))?;
+        serde_json::from_str(&content)
+            .map_err(|e| format!("invalid bookmarks '{}', {}", self.path.display(), e))
+    }
+
+    // offset of book with given hash, none if it was not started
+    pub fn load(&self, hash: u64) -> Response<Option<Bookmark>> {
+        match self.read() {
+            Ok(offsets) => Response::plain(offsets.get(&format!("{:016x}", hash)).map(|&offset| Bookmark { hash, offset })),
+            Err(e) => Response::with_warning(None, e),
+        }
+    }
+
+    pub fn save(&mut self, bookmark: Bookmark) -> Response<()> {
+        let mut offsets = match self.read() {
+            Ok(offsets) => offsets,
+            Err(e) => return Response::with_error((), e),
+        };
+        offsets.insert(format!("{:016x}", bookmark.hash), bookmark.offset);
+
+        let content = match serde_json::to_string_pretty(&offsets) {
+            Ok(content) => content,
+            Err(e) => return Response::with_error((), format!("cannot serialize bookmarks, {}", e)),
+        };
+
+        if let Some(parent) = self.path.parent() {
+            if let Err(e) = fs::create_dir_all(parent) {
+                return Response::with_error((), format!("cannot create directory '{}', {}", parent.display(), e));
+            }
+        }
+
+        match fs::write(&self.path, content) {
+            Ok(_) => Response::plain(()),
+            Err(e) => Response::with_error((), format!("cannot write bookmarks '{}', {}", self.path.display(), e)),
+        }
+    }
+}
+
+// api function, that returns passage of book from config file, where its bookmark points
+// passage size is word count of config
+#[cfg(not(target_arch = "wasm32"))]
+pub fn generate_passage(config: &Config, store: Option<&BookmarkStore>) -> PassageResponse {
+    if config.mode != GameMode::Book {
+        return PassageResponse::with_error(None, "passages are available only in book mode".to_string());
+    }
+
+    let Some(file) = &config.file else {
+        return PassageResponse::with_error(None, "book mode needs a file".to_string());
+    };
+
+    let text = match load_text(file, config) {
+        Ok(text) => text,
+        Err(e) => return PassageResponse::with_error(None, format!("invalid file '{}', {}", file, e)),
+    };
+
+    // book without bookmark starts from the beginning
+    let bookmark = store.map_or(Response::plain(None), |store| store.load(book_hash(&text)));
+    let offset = bookmark.payload.map_or(0, |bookmark| bookmark.offset);
+
+    let passage = passage_at(&text, offset, config.word_count);
+
+    if passage.words.is_empty() {
+        return PassageResponse::with_error(None, format!("book '{}' has no words", file));
+    }
+    if let Some(message) = bookmark.message {
+        return PassageResponse { payload: Some(passage), message: Some(message) };
+    }
+    if offset > 0 && passage.start.offset == 0 {
+        return PassageResponse::with_info(Some(passage), "book finished, starting over".to_string());
+    }
+
+    PassageResponse::plain(Some(passage))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::validate_config;
+
+    #[test]
+    fn passage_continues_from_bookmark() {
+        let dir = std::env::temp_dir().join(format!("typecrab-book-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("book.txt");
+        fs::write(&file, "Call me\nIshmael. Some years\n\nago, never mind\nhow long.\n").unwrap();
+
+        let config = validate_config(Config {
+            mode: GameMode::Book,
+            file: Some(file.display().to_string()),
+            word_count: 4,
+            ..Config::default()
+        });
+        assert!(config.message.is_none());
+
+        let mut store = BookmarkStore::new(dir.join("bookmarks.json"));
+        let first = generate_passage(&config.payload, Some(&store)).payload.unwrap();
+        assert_eq!(first.words, ["Call", "me", "Ishmael.", "Some"]);
+
+        store.save(first.next());
+        let second = generate_passage(&config.payload, Some(&store)).payload.unwrap();
+        assert_eq!(second.words, ["years\n", "ago,", "never", "mind"]);
+        assert_eq!(second.progress(4), 80.0);
+
+        store.save(second.next());
+        let last = generate_passage(&config.payload, Some(&store)).payload.unwrap();
+        assert_eq!(last.words, ["how", "long."]);
+
+        // finished book starts over
+        store.save(last.next());
+        let again = generate_passage(&config.payload, Some(&store));
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(again.payload.unwrap().start.offset, 0);
+        assert_eq!(again.message.unwrap().1, "book finished, starting over");
+    }
+}
@@ -0,0 +1,70 @@
+/*
+ * core/src/clock.rs
+ */
+
+use std::{
+    cell::Cell,
+    fmt::Debug,
+    rc::Rc,
+    sync::OnceLock,
+    time::Duration,
+};
+use instant::Instant;
+
+// time source for tests, only differences between readings matter
+pub trait Clock: Debug {
+    fn now(&self) -> Duration;
+}
+
+// monotonic system clock, all instances share one origin
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        static ORIGIN: OnceLock<Instant> = OnceLock::new();
+        ORIGIN.get_or_init(Instant::now).elapsed()
+    }
+}
+
+// manually driven clock, clones share the same time
+#[derive(Debug, Clone, Default)]
+pub struct ManualClock {
+    now: Rc<Cell<Duration>>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&self, time: Duration) {
+        self.now.set(time);
+    }
+
+    pub fn advance(&self, by: Duration) {
+        self.now.set(self.now.get() + by);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        self.now.get()
+    }
+}
+
+// browser high resolution clock, same time base as event timestamps (performance.now)
+#[cfg(target_arch = "wasm32")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BrowserClock;
+
+#[cfg(target_arch = "wasm32")]
+impl Clock for BrowserClock {
+    fn now(&self) -> Duration {
+        let millis = web_sys::window()
+            .and_then(|w| w.performance())
+            .map(|p| p.now())
+            .unwrap_or(0.0);
+        Duration::from_secs_f64(millis / 1000.0)
+    }
+}
@@ -7,10 +7,16 @@ use crate::{
         Level,
         Response
     },
-    languages::{WordsLanguages, QuotesLanguages}
+    registry::{
+        is_code_pack,
+        DEFAULT_CODE_LANGUAGE,
+        DEFAULT_LANGUAGE
+    },
+    quotes::QuoteLength,
+    markup::TextFormat
 };
 
-use serde::{Serialize, Deserialize};
+use serde::{Serialize, Deserialize, Deserializer};
 
 pub type ConfigResponse = Response<Config>;
 
@@ -19,12 +25,61 @@ pub enum GameMode {
     Words,
     Quote,
     Zen,
+    Book,   // long text from file typed in passages, see book
+    Code,   // source snippet typed with its indentation and line breaks
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+impl GameMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GameMode::Words => "words",
+            GameMode::Quote => "quote",
+            GameMode::Zen => "zen",
+            GameMode::Book => "book",
+            GameMode::Code => "code",
+        }
+    }
+}
+
+// language is name of words or quotes pack, see registry
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum Language {
-    Words(WordsLanguages),
-    Quotes(QuotesLanguages)
+    Words(#[serde(deserialize_with = "pack_name")] String),
+    Quotes(#[serde(deserialize_with = "pack_name")] String)
+}
+
+impl Language {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Language::Words(lang) => lang,
+            Language::Quotes(lang) => lang,
+        }
+    }
+}
+
+impl Default for Language {
+    fn default() -> Self {
+        Language::Words(DEFAULT_LANGUAGE.to_string())
+    }
+}
+
+// pack name, older configs stored generated enum variants like 'CodeCpp' for 'code-cpp'
+fn pack_name<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    let name = String::deserialize(deserializer)?;
+
+    if !name.starts_with(|c: char| c.is_ascii_uppercase()) {
+        return Ok(name);
+    }
+
+    let mut kebab = String::new();
+    for c in name.chars() {
+        if c.is_ascii_uppercase() && !kebab.is_empty() {
+            kebab.push('-');
+        }
+        kebab.push(c.to_ascii_lowercase());
+    }
+
+    Ok(kebab)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -38,13 +93,37 @@ pub struct Config {
     pub numbers: bool,
     pub backtrack: bool,
     pub death: bool,
+    #[serde(default)]
+    pub seed: Option<u64>,      // same seed and config = same test content
+    #[serde(default)]
+    pub daily: bool,            // daily challenge, content is chosen by date and language
+    #[serde(default)]
+    pub top_words: Option<usize>,   // pick only from n most frequent words
+    #[serde(default)]
+    pub weighted: bool,         // pick words by their frequency
+    #[serde(default)]
+    pub quote_length: Option<QuoteLength>,  // pick only quotes of given length
+    #[serde(default)]
+    pub quote_id: Option<String>,   // pick exactly this quote
+    #[serde(default)]
+    pub repo: Option<String>,       // source tree, code mode picks its blocks
+    #[serde(default)]
+    pub strip_comments: bool,       // snippets of source tree without comments
+    #[serde(default)]
+    pub text_format: Option<TextFormat>,    // markup of custom file, by its extension if none
+    #[serde(default)]
+    pub ascii: bool,                // custom file with typographic characters folded to ascii
+    #[serde(default)]
+    pub lenient_accents: bool,      // letters match regardless of accents, e.g. 'a' for 'á'
+    #[serde(skip)]
+    pub day: Option<u64>,           // day daily challenge text is generated for, today if none
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             mode: GameMode::Words,
-            language: Language::Words(WordsLanguages::En),
+            language: Language::default(),
             file: None,
             word_count: 25,
             time_limit: None,
@@ -52,6 +131,18 @@ impl Default for Config {
             numbers: false,
             backtrack: true,
             death: false,
+            seed: None,
+            daily: false,
+            top_words: None,
+            weighted: false,
+            quote_length: None,
+            quote_id: None,
+            repo: None,
+            strip_comments: false,
+            text_format: None,
+            ascii: false,
+            lenient_accents: false,
+            day: None,
         }
     }
 }
@@ -84,12 +175,122 @@ pub fn validate_config(mut config: Config) -> ConfigResponse {
         level.escalate(Level::Warning);
     }
 
+    if let Some(0) = config.top_words {
+        config.top_words = None;
+        messages.push("invalid top words count, disabled".to_string());
+        level.escalate(Level::Warning);
+    }
+
     // custom file validation
     if let Some(_) = config.file {
         if matches!(config.mode, GameMode::Zen) {
             messages.push("provided custom file, but chosen zen mode".to_string());
             level.escalate(Level::Error);
         }
+        if config.quote_length.is_some() {
+            config.quote_length = None;
+            messages.push("custom file ignores quote length".to_string());
+            level.escalate(Level::Warning);
+        }
+        if config.quote_id.is_some() {
+            config.quote_id = None;
+            messages.push("custom file ignores quote id".to_string());
+            level.escalate(Level::Warning);
+        }
+    }
+
+    // source tree validation
+    if config.repo.is_some() {
+        if config.mode != GameMode::Code {
+            config.repo = None;
+            messages.push(format!("{} mode ignores source tree", config.mode.as_str()));
+            level.escalate(Level::Warning);
+        } else if config.file.is_some() {
+            messages.push("provided custom file and source tree".to_string());
+            level.escalate(Level::Error);
+        }
+    }
+    if config.strip_comments && config.repo.is_none() {
+        config.strip_comments = false;
+        messages.push("comments are stripped only from source tree".to_string());
+        level.escalate(Level::Warning);
+    }
+
+    // daily challenge validation, everyone has to get the same test
+    if config.daily {
+        if matches!(config.mode, GameMode::Zen | GameMode::Book) {
+            messages.push(format!("daily challenge has no {} mode, fallback to 'words' mode", config.mode.as_str()));
+            config.mode = GameMode::Words;
+            config.language = Language::default();
+            level.escalate(Level::Warning);
+        }
+        if config.file.is_some() {
+            config.file = None;
+            messages.push("daily challenge ignores custom file".to_string());
+            level.escalate(Level::Warning);
+        }
+        if config.repo.is_some() {
+            config.repo = None;
+            config.strip_comments = false;
+            messages.push("daily challenge ignores source tree".to_string());
+            level.escalate(Level::Warning);
+        }
+        if config.seed.is_some() {
+            config.seed = None;
+            messages.push("daily challenge ignores seed".to_string());
+            level.escalate(Level::Warning);
+        }
+        if config.mode == GameMode::Words && config.word_count != 25 {
+            config.word_count = 25;
+            messages.push("daily challenge ignores word count".to_string());
+            level.escalate(Level::Warning);
+        }
+        if config.punctuation {
+            config.punctuation = false;
+            messages.push("daily challenge ignores punctuation".to_string());
+            level.escalate(Level::Warning);
+        }
+        if config.numbers {
+            config.numbers = false;
+            messages.push("daily challenge ignores numbers".to_string());
+            level.escalate(Level::Warning);
+        }
+        if config.time_limit.is_some() {
+            config.time_limit = None;
+            messages.push("daily challenge ignores time limit".to_string());
+            level.escalate(Level::Warning);
+        }
+        if config.top_words.is_some() || config.weighted {
+            config.top_words = None;
+            config.weighted = false;
+            messages.push("daily challenge ignores word frequency options".to_string());
+            level.escalate(Level::Warning);
+        }
+        if config.quote_length.is_some() {
+            config.quote_length = None;
+            messages.push("daily challenge ignores quote length".to_string());
+            level.escalate(Level::Warning);
+        }
+        if config.quote_id.is_some() {
+            config.quote_id = None;
+            messages.push("daily challenge ignores quote id".to_string());
+            level.escalate(Level::Warning);
+        }
+    }
+
+    // text cleaning validation, after daily challenge dropped custom file
+    if config.text_format.is_some() || config.ascii {
+        if config.file.is_none() {
+            config.text_format = None;
+            config.ascii = false;
+            messages.push("text format and ascii folding are used only for custom file".to_string());
+            level.escalate(Level::Warning);
+        } else if config.mode == GameMode::Code {
+            config.text_format = None;
+            config.ascii = false;
+            messages.push("code mode keeps custom file as is".to_string());
+            level.escalate(Level::Warning);
+        }
     }
 
     // mode-specific validation
@@ -97,7 +298,17 @@ pub fn validate_config(mut config: Config) -> ConfigResponse {
         GameMode::Words => {
             if !matches!(config.language, Language::Words(_)) {
                 messages.push("invalid language for words mode, fallback to 'en'".to_string());
-                config.language = Language::Words(WordsLanguages::En);
+                config.language = Language::default();
+                level.escalate(Level::Warning);
+            }
+            if config.quote_length.is_some() {
+                config.quote_length = None;
+                messages.push("words mode ignores quote length".to_string());
+                level.escalate(Level::Warning);
+            }
+            if config.quote_id.is_some() {
+                config.quote_id = None;
+                messages.push("words mode ignores quote id".to_string());
                 level.escalate(Level::Warning);
             }
         }
@@ -106,7 +317,9 @@ pub fn validate_config(mut config: Config) -> ConfigResponse {
             if !matches!(config.language, Language::Quotes(_)) {
                 messages.push("invalid language for quote mode, fallback to 'words' mode".to_string());
                 config.mode = GameMode::Words;
-                config.language = Language::Words(WordsLanguages::En);
+                config.language = Language::default();
+                config.quote_length = None;
+                config.quote_id = None;
                 level.escalate(Level::Warning);
             } else {
                 if config.word_count != 25 {
@@ -124,6 +337,75 @@ pub fn validate_config(mut config: Config) -> ConfigResponse {
                     messages.push("quote mode ignores numbers".to_string());
                     level.escalate(Level::Warning);
                 }
+                if config.top_words.is_some() || config.weighted {
+                    config.top_words = None;
+                    config.weighted = false;
+                    messages.push("quote mode ignores word frequency options".to_string());
+                    level.escalate(Level::Warning);
+                }
+                if config.quote_id.is_some() && config.quote_length.is_some() {
+                    config.quote_length = None;
+                    messages.push("chosen quote id ignores quote length".to_string());
+                    level.escalate(Level::Warning);
+                }
+            }
+        }
+
+        GameMode::Code => {
+            if !matches!(&config.language, Language::Quotes(lang) if is_code_pack(lang)) {
+                messages.push(format!("invalid language for code mode, fallback to '{}'", DEFAULT_CODE_LANGUAGE));
+                config.language = Language::Quotes(DEFAULT_CODE_LANGUAGE.to_string());
+                config.quote_id = None;
+                level.escalate(Level::Warning);
+            }
+            if config.word_count != 25 {
+                config.word_count = 25;
+                messages.push("code mode ignores word count".to_string());
+                level.escalate(Level::Warning);
+            }
+            if config.punctuation {
+                config.punctuation = false;
+                messages.push("code mode ignores punctuation".to_string());
+                level.escalate(Level::Warning);
+            }
+            if config.numbers {
+                config.numbers = false;
+                messages.push("code mode ignores numbers".to_string());
+                level.escalate(Level::Warning);
+            }
+            if config.top_words.is_some() || config.weighted {
+                config.top_words = None;
+                config.weighted = false;
+                messages.push("code mode ignores word frequency options".to_string());
+                level.escalate(Level::Warning);
+            }
+            if config.quote_id.is_some() && config.quote_length.is_some() {
+                config.quote_length = None;
+                messages.push("chosen snippet id ignores quote length".to_string());
+                level.escalate(Level::Warning);
+            }
+        }
+
+        GameMode::Book => {
+            if config.file.is_none() {
+                messages.push("book mode needs a file".to_string());
+                level.escalate(Level::Error);
+            }
+            if config.punctuation {
+                config.punctuation = false;
+                messages.push("book mode ignores punctuation".to_string());
+                level.escalate(Level::Warning);
+            }
+            if config.numbers {
+                config.numbers = false;
+                messages.push("book mode ignores numbers".to_string());
+                level.escalate(Level::Warning);
+            }
+            if config.top_words.is_some() || config.weighted {
+                config.top_words = None;
+                config.weighted = false;
+                messages.push("book mode ignores word frequency options".to_string());
+                level.escalate(Level::Warning);
             }
         }
 
@@ -143,6 +425,22 @@ pub fn validate_config(mut config: Config) -> ConfigResponse {
                 messages.push("zen mode ignores numbers".to_string());
                 level.escalate(Level::Warning);
             }
+            if config.top_words.is_some() || config.weighted {
+                config.top_words = None;
+                config.weighted = false;
+                messages.push("zen mode ignores word frequency options".to_string());
+                level.escalate(Level::Warning);
+            }
+            if config.quote_length.is_some() {
+                config.quote_length = None;
+                messages.push("zen mode ignores quote length".to_string());
+                level.escalate(Level::Warning);
+            }
+            if config.quote_id.is_some() {
+                config.quote_id = None;
+                messages.push("zen mode ignores quote id".to_string());
+                level.escalate(Level::Warning);
+            }
             if !config.backtrack {
                 config.backtrack = true;
                 messages.push("zen mode ignores strict mode".to_string());
@@ -158,6 +456,11 @@ pub fn validate_config(mut config: Config) -> ConfigResponse {
                 messages.push("zen mode ignores time limit".to_string());
                 level.escalate(Level::Warning);
             }
+            if config.lenient_accents {
+                config.lenient_accents = false;
+                messages.push("zen mode ignores lenient accents".to_string());
+                level.escalate(Level::Warning);
+            }
         }
     }
 
@@ -0,0 +1,70 @@
+/*
+ * core/src/daily.rs
+ */
+
+use crate::{
+    config::{
+        Config,
+        GameMode
+    },
+    history::timestamp_now,
+};
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+// 64-bit fnv-1a parameters
+const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+// api function, that returns current utc day as number of days since unix epoch
+pub fn today() -> u64 {
+    day_of(timestamp_now())
+}
+
+// utc day of unix timestamp in seconds
+pub fn day_of(timestamp: u64) -> u64 {
+    timestamp / SECONDS_PER_DAY
+}
+
+// api function, that returns seed of daily challenge, same for everyone on given day, mode and language
+pub fn daily_seed(day: u64, config: &Config) -> u64 {
+    let key = format!("{}/{}/{}", day, config.mode.as_str(), config.language.as_str());
+    fnv_hash(key.as_bytes())
+}
+
+// stable 64-bit hash, same on every platform and build
+pub(crate) fn fnv_hash(bytes: &[u8]) -> u64 {
+    let mut hash = FNV_OFFSET;
+
+    for &byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+
+    hash
+}
+
+// seed content is generated with, daily challenge overrides config seed
+pub(crate) fn content_seed(config: &Config) -> Option<u64> {
+    if config.daily && config.mode != GameMode::Zen {
+        Some(daily_seed(config.day.unwrap_or_else(today), config))
+    } else {
+        config.seed
+    }
+}
+
+// api function, that formats day number as yyyy-mm-dd
+pub fn date_string(day: u64) -> String {
+    // days to civil date, proleptic gregorian calendar
+    let z = day as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
@@ -6,14 +6,21 @@ use std::{
     fs,
     io::{
         self,
-        BufRead
+        IsTerminal,
+        Read
     },
-    path::Path
+    sync::OnceLock
 };
 use rand::{
+    distr::{
+        weighted::WeightedIndex,
+        Distribution
+    },
     prelude::IndexedRandom,
+    rngs::StdRng,
     rng,
     Rng,
+    SeedableRng,
 };
 
 use crate::{
@@ -23,18 +30,45 @@ use crate::{
         Language
     },
     response::Response,
+    results::{
+        missed_words,
+        RawResults
+    },
+    test::Test,
+    daily::content_seed,
+    quotes::{
+        Quote,
+        QuoteLength,
+        QuoteResponse,
+        QuoteListResponse
+    },
+    registry::{
+        find_pack,
+        is_code_pack,
+        PackKind
+    },
+    markup::{
+        clean_text,
+        fold_ascii,
+        TextFormat
+    },
 };
 
 #[cfg(target_arch = "wasm32")]
 use crate::{
-    languages::{
+    book::PassageResponse,
+    registry::{
         BASE_PATH
     }
 };
+
 #[cfg(not(target_arch = "wasm32"))]
 use crate::{
-    WORDS_DIR,
-    QUOTES_DIR,
+    book::{
+        generate_passage,
+        BookmarkStore
+    },
+    repo::repo_snippets
 };
 
 const PUNCTS: &[&str] = &[".", ",", "!", "?", ":", ";"];
@@ -43,80 +77,377 @@ const NUMBER_RANGE: std::ops::RangeInclusive<u32> = 1..=9999;
 const PUNCT_PROBABILITY: f64 = 0.2;
 const NUMBER_PROBABILITY: f64 = 0.2;
 
+const PRACTICE_BOOST: f64 = 20.0;      // extra weight of practice word for every target it contains
+
+const STREAM_LOOKAHEAD: usize = 10;    // words left after caret, when stream appends more
+const STREAM_CHUNK: usize = 25;        // words appended at once
+
+// file name of standard input, e.g. for text piped to typecrab
+pub const STDIN_FILE: &str = "-";
+
+// standard input can be read only once, every next test gets the same text
+static STDIN_TEXT: OnceLock<Result<String, (io::ErrorKind, String)>> = OnceLock::new();
+
 pub type GeneratorResponse = Response<Vec<String>>;
+pub type StreamResponse = Response<Option<WordStream>>;
+
+// api function, that returns random seed, e.g. to fill config before generation and show it later
+pub fn random_seed() -> u64 {
+    rng().random()
+}
+
+// deterministic generator if seed is given, random otherwise
+fn seeded_rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_rng(&mut rng()),
+    }
+}
+
+// word lists are ordered from the most frequent word, every line is 'word' or 'word<TAB>weight'
+// words without explicit weight are weighted by rank (zipf's law)
+fn parse_word_list(lines: Vec<String>) -> Vec<(String, f64)> {
+    let mut words = Vec::new();
+
+    for line in lines {
+        if let Some((word, weight)) = line.split_once('\t') {
+            if let Ok(weight) = weight.trim().parse::<f64>() {
+                let word = word.trim();
+                if !word.is_empty() {
+                    words.push((word.to_string(), weight));
+                }
+                continue;
+            }
+        }
+
+        for word in line.split_whitespace() {
+            let rank = words.len() + 1;
+            words.push((word.to_string(), 1.0 / rank as f64));
+        }
+    }
+
+    words
+}
+
+// endless words source, keeps timed words tests from running out of words
+#[derive(Debug, Clone)]
+pub struct WordStream {
+    base_words: Vec<String>,
+    weights: Option<WeightedIndex<f64>>,    // none = uniform choice
+    punctuation: bool,
+    numbers: bool,
+    rng: StdRng,
+}
+
+impl WordStream {
+    pub fn new(lines: Vec<String>, config: &Config) -> Self {
+        Self::with_targets(lines, config, &[])
+    }
+
+    // stream biased toward words containing given characters or bigrams
+    pub fn with_targets(lines: Vec<String>, config: &Config, targets: &[String]) -> Self {
+        let mut words = parse_word_list(lines);
+
+        // only n most frequent words
+        if let Some(n) = config.top_words {
+            words.truncate(n);
+        }
+
+        // invalid weights (e.g. all zero) fallback to uniform choice
+        let weights = if config.weighted || !targets.is_empty() {
+            WeightedIndex::new(words.iter().map(|(word, frequency)| {
+                let base = if config.weighted { *frequency } else { 1.0 };
+                let hits = targets.iter().filter(|t| word.to_lowercase().contains(t.as_str())).count();
+                base * (1.0 + PRACTICE_BOOST * hits as f64)
+            })).ok()
+        } else {
+            None
+        };
+
+        Self {
+            base_words: words.into_iter().map(|(word, _)| word).collect(),
+            weights,
+            punctuation: config.punctuation,
+            numbers: config.numbers,
+            rng: seeded_rng(content_seed(config)),
+        }
+    }
+
+    // stream that continues words generated by generate_content or generate_practice with the same config and targets
+    fn after_content(lines: Vec<String>, config: &Config, targets: &[String]) -> Self {
+        let mut stream = Self::with_targets(lines, config, targets);
+        stream.next_words(config.word_count);
+        stream
+    }
+
+    // next n words with punctuation and numbers applied, numbers come as separate words
+    pub fn next_words(&mut self, n: usize) -> Vec<String> {
+        let rng = &mut self.rng;
+        let mut result = Vec::new();
+
+        for _ in 0..n {
+            let mut word = match &self.weights {
+                Some(weights) => self.base_words[weights.sample(rng)].clone(),
+                None => self.base_words.choose(rng).cloned().unwrap_or_default(),
+            };
+
+            // punctuation
+            if self.punctuation && rng.random_bool(PUNCT_PROBABILITY) {
+                word.push_str(PUNCTS.choose(rng).unwrap());
+            }
+
+            result.push(word);
+
+            // numbers
+            if self.numbers && rng.random_bool(NUMBER_PROBABILITY) {
+                let number = rng.random_range(NUMBER_RANGE);
+                result.push(number.to_string());
+            }
+        }
+
+        result
+    }
+
+    // api function, that appends words to running test when caret gets close to the end
+    // returns appended words, empty if test has enough of them
+    pub fn fill(&mut self, test: &mut Test) -> Vec<String> {
+        if test.complete || self.base_words.is_empty() || test.words.len() > test.current_word + STREAM_LOOKAHEAD {
+            return Vec::new();
+        }
+
+        let words = self.next_words(STREAM_CHUNK);
+        test.extend_words(words.clone());
+        words
+    }
+}
 
 // api function, that generates test content according to config
 macro_rules! generate_content {
     ($($maybe_async:tt)?) => {
         pub $($maybe_async)? fn generate_content(config: &Config) -> GeneratorResponse {
-            // if user specified file, read from it directly
-            if let Some(user_file) = &config.file {
-                let lines = match load_file(user_file) {
-                    Ok(lines) => lines,
-                    Err(e) => {
-                        return GeneratorResponse::with_error(
-                            Vec::new(),
-                            format!("invalid file '{}', {}", user_file, e),
-                        );
-                    }
-                };
-
-                return match config.mode {
-                    GameMode::Words => GeneratorResponse::plain(finalize_lines(lines, config)),
-                    GameMode::Quote => GeneratorResponse::plain(split_lines(lines)),
-                    GameMode::Zen => GeneratorResponse::plain(vec![String::new()]),
-                };
-            }
-
             match config.mode {
                 GameMode::Words => {
-                    if let Language::Words(lang) = config.language {
-                        #[cfg(target_arch = "wasm32")]
-                        let lines = match load_words(lang.as_str()).await {
-                            Ok(lines) => lines,
-                            Err(e) => return GeneratorResponse::with_error(Vec::new(), e),
-                        };
-
-                        #[cfg(not(target_arch = "wasm32"))]
-                        let lines = match load_words(lang.as_str()) {
-                            Ok(lines) => lines,
-                            Err(e) => return GeneratorResponse::with_error(Vec::new(), e),
-                        };
-
-                        GeneratorResponse::plain(finalize_lines(lines, config))
-                    } else {
-                        GeneratorResponse::with_error(
-                            Vec::new(),
-                            "invalid language for words mode".to_string(),
-                        )
+                    #[cfg(target_arch = "wasm32")]
+                    let lines = load_word_lines(config).await;
+
+                    #[cfg(not(target_arch = "wasm32"))]
+                    let lines = load_word_lines(config);
+
+                    match lines {
+                        Ok(lines) => GeneratorResponse::plain(finalize_lines(lines, config)),
+                        Err(e) => GeneratorResponse::with_error(Vec::new(), e),
                     }
                 }
 
                 GameMode::Quote => {
-                    if let Language::Quotes(lang) = config.language {
-
-                        #[cfg(target_arch = "wasm32")]
-                        let lines = match load_quote(lang.as_str()).await {
-                            Ok(lines) => lines,
-                            Err(e) => return GeneratorResponse::with_error(Vec::new(), e),
-                        };
-                        #[cfg(not(target_arch = "wasm32"))]
-                        let lines = match load_quote(lang.as_str()) {
-                            Ok(lines) => lines,
-                            Err(e) => return GeneratorResponse::with_error(Vec::new(), e),
-                        };
-                        GeneratorResponse::plain(split_lines(lines))
-                    } else {
-                        GeneratorResponse::with_error(
-                            Vec::new(),
-                            "invalid language for quote mode".to_string(),
-                        )
+                    #[cfg(target_arch = "wasm32")]
+                    let response = generate_quote(config).await;
+
+                    #[cfg(not(target_arch = "wasm32"))]
+                    let response = generate_quote(config);
+
+                    GeneratorResponse {
+                        payload: response.payload.as_ref().map(Quote::words).unwrap_or_default(),
+                        message: response.message,
+                    }
+                }
+
+                GameMode::Code => {
+                    #[cfg(target_arch = "wasm32")]
+                    let response = generate_quote(config).await;
+
+                    #[cfg(not(target_arch = "wasm32"))]
+                    let response = generate_quote(config);
+
+                    GeneratorResponse {
+                        payload: response.payload.as_ref().map(Quote::code_words).unwrap_or_default(),
+                        message: response.message,
                     }
                 }
 
                 GameMode::Zen => {
                     GeneratorResponse::plain(vec![String::new()])
                 }
+
+                // books are read from file system, bookmarks are kept in user data directory
+                GameMode::Book => {
+                    #[cfg(target_arch = "wasm32")]
+                    let response = PassageResponse::with_error(None, "book mode is not available in browser".to_string());
+
+                    #[cfg(not(target_arch = "wasm32"))]
+                    let response = generate_passage(config, BookmarkStore::open_default().as_ref());
+
+                    GeneratorResponse {
+                        payload: response.payload.map(|passage| passage.words).unwrap_or_default(),
+                        message: response.message,
+                    }
+                }
+            }
+        }
+    };
+}
+
+// api function, that picks quote for quote or code mode test, from custom file or language
+// its words are test content, metadata is for attribution on results
+macro_rules! generate_quote {
+    ($($maybe_async:tt)?) => {
+        pub $($maybe_async)? fn generate_quote(config: &Config) -> QuoteResponse {
+            if !matches!(config.mode, GameMode::Quote | GameMode::Code) {
+                return QuoteResponse::with_error(None, "quotes are available only in quote and code modes".to_string());
+            }
+
+            // code block of user source tree
+            if let Some(repo) = &config.repo {
+                #[cfg(target_arch = "wasm32")]
+                return QuoteResponse::with_error(None, format!("source tree '{}' is not available in browser", repo));
+
+                #[cfg(not(target_arch = "wasm32"))]
+                return match load_snippet(repo, config) {
+                    Ok(quote) => QuoteResponse::plain(Some(quote)),
+                    Err(e) => QuoteResponse::with_error(None, e),
+                };
+            }
+
+            // if user specified file, read from it directly, code is kept as is
+            if let Some(user_file) = &config.file {
+                let text = if config.mode == GameMode::Code { read_file(user_file) } else { load_text(user_file, config) };
+                return match text {
+                    Ok(text) => QuoteResponse::plain(Some(Quote { code: config.mode == GameMode::Code, ..Quote::parse(user_file, &text) })),
+                    Err(e) => QuoteResponse::with_error(None, format!("invalid file '{}', {}", user_file, e)),
+                };
+            }
+
+            let Language::Quotes(lang) = &config.language else {
+                return QuoteResponse::with_error(None, "invalid language for quote mode".to_string());
+            };
+
+            #[cfg(target_arch = "wasm32")]
+            let quote = load_quote(lang.as_str(), content_seed(config), config.quote_length, config.quote_id.as_deref()).await;
+
+            #[cfg(not(target_arch = "wasm32"))]
+            let quote = load_quote(lang.as_str(), content_seed(config), config.quote_length, config.quote_id.as_deref());
+
+            match quote {
+                Ok(quote) => QuoteResponse::plain(Some(quote)),
+                Err(e) => QuoteResponse::with_error(None, e),
+            }
+        }
+    };
+}
+
+// api function, that lists all quotes of quotes language, e.g. to search them or pick one by id
+macro_rules! list_quotes {
+    ($($maybe_async:tt)?) => {
+        pub $($maybe_async)? fn list_quotes(language: &Language) -> QuoteListResponse {
+            let Language::Quotes(lang) = language else {
+                return QuoteListResponse::with_error(Vec::new(), "quotes are available only for quotes language".to_string());
+            };
+
+            #[cfg(target_arch = "wasm32")]
+            let quotes = load_quotes(lang.as_str()).await;
+
+            #[cfg(not(target_arch = "wasm32"))]
+            let quotes = load_quotes(lang.as_str());
+
+            match quotes {
+                Ok(quotes) if quotes.is_empty() => QuoteListResponse::with_error(quotes, format!("no quotes in '{}'", lang)),
+                Ok(quotes) => QuoteListResponse::plain(quotes),
+                Err(e) => QuoteListResponse::with_error(Vec::new(), e),
+            }
+        }
+    };
+}
+
+// words list for words mode, from custom file or language
+macro_rules! load_word_lines {
+    ($($maybe_async:tt)?) => {
+        $($maybe_async)? fn load_word_lines(config: &Config) -> Result<Vec<String>, String> {
+            if let Some(user_file) = &config.file {
+                return load_word_list(user_file, config)
+                    .map_err(|e| format!("invalid file '{}', {}", user_file, e));
+            }
+
+            if let Language::Words(lang) = &config.language {
+                #[cfg(target_arch = "wasm32")]
+                return load_words(lang.as_str()).await;
+
+                #[cfg(not(target_arch = "wasm32"))]
+                return load_words(lang.as_str());
+            }
+
+            Err("invalid language for words mode".to_string())
+        }
+    };
+}
+
+// api function, that prepares words stream for timed words tests, none for other tests
+// practice stream keeps words biased toward the same targets as generate_practice
+macro_rules! generate_stream {
+    ($($maybe_async:tt)?) => {
+        pub $($maybe_async)? fn generate_stream(config: &Config) -> StreamResponse {
+            if config.mode != GameMode::Words || config.time_limit.is_none() {
+                return StreamResponse::plain(None);
+            }
+
+            #[cfg(target_arch = "wasm32")]
+            let lines = load_word_lines(config).await;
+
+            #[cfg(not(target_arch = "wasm32"))]
+            let lines = load_word_lines(config);
+
+            match lines {
+                Ok(lines) => StreamResponse::plain(Some(WordStream::after_content(lines, config, &[]))),
+                Err(e) => StreamResponse::with_error(None, e),
+            }
+        }
+
+        pub $($maybe_async)? fn generate_practice_stream(config: &Config, targets: &[String]) -> StreamResponse {
+            if config.mode != GameMode::Words || config.time_limit.is_none() {
+                return StreamResponse::plain(None);
+            }
+
+            #[cfg(target_arch = "wasm32")]
+            let lines = load_word_lines(config).await;
+
+            #[cfg(not(target_arch = "wasm32"))]
+            let lines = load_word_lines(config);
+
+            let targets = targets.iter().map(|t| t.to_lowercase()).collect::<Vec<_>>();
+            match lines {
+                Ok(lines) => StreamResponse::plain(Some(WordStream::after_content(lines, config, &targets))),
+                Err(e) => StreamResponse::with_error(None, e),
+            }
+        }
+    };
+}
+
+// api function, that generates words mode content biased toward practice targets
+// targets are characters or bigrams, e.g. from weak_targets
+macro_rules! generate_practice {
+    ($($maybe_async:tt)?) => {
+        pub $($maybe_async)? fn generate_practice(config: &Config, targets: &[String]) -> GeneratorResponse {
+            if config.mode != GameMode::Words {
+                return GeneratorResponse::with_error(Vec::new(), "practice is available only in words mode".to_string());
+            }
+
+            #[cfg(target_arch = "wasm32")]
+            let lines = load_word_lines(config).await;
+
+            #[cfg(not(target_arch = "wasm32"))]
+            let lines = load_word_lines(config);
+
+            let lines = match lines {
+                Ok(lines) => lines,
+                Err(e) => return GeneratorResponse::with_error(Vec::new(), e),
+            };
+
+            let targets = targets.iter().map(|t| t.to_lowercase()).collect::<Vec<_>>();
+            let words = WordStream::with_targets(lines, config, &targets).next_words(config.word_count);
+
+            if targets.is_empty() {
+                GeneratorResponse::with_info(words, "no practice targets, words are not biased".to_string())
+            } else {
+                GeneratorResponse::with_info(words, format!("practice targets {}", targets.join(" ")))
             }
         }
     };
@@ -129,6 +460,36 @@ generate_content!(async);
 #[cfg(not(target_arch = "wasm32"))]
 generate_content!();
 
+#[cfg(target_arch = "wasm32")]
+generate_quote!(async);
+
+#[cfg(not(target_arch = "wasm32"))]
+generate_quote!();
+
+#[cfg(target_arch = "wasm32")]
+list_quotes!(async);
+
+#[cfg(not(target_arch = "wasm32"))]
+list_quotes!();
+
+#[cfg(target_arch = "wasm32")]
+load_word_lines!(async);
+
+#[cfg(not(target_arch = "wasm32"))]
+load_word_lines!();
+
+#[cfg(target_arch = "wasm32")]
+generate_stream!(async);
+
+#[cfg(not(target_arch = "wasm32"))]
+generate_stream!();
+
+#[cfg(target_arch = "wasm32")]
+generate_practice!(async);
+
+#[cfg(not(target_arch = "wasm32"))]
+generate_practice!();
+
 
 #[cfg(target_arch = "wasm32")]
 fn base_url() -> String {
@@ -159,97 +520,154 @@ async fn load_words(lang: &str) -> Result<Vec<String>, String> {
 
 #[cfg(not(target_arch = "wasm32"))]
 fn load_words(lang: &str) -> Result<Vec<String>, String> {
-    let path = Path::new(WORDS_DIR).join(format!("{}.txt", lang));
-    load_file(&path).map_err(|e| format!("cannot read words '{}', {}", path.display(), e))
+    let pack = find_pack(PackKind::Words, lang).ok_or_else(|| format!("unknown words language '{}'", lang))?;
+    let text = pack.read().map_err(|e| format!("cannot read words, {}", e))?;
+    Ok(text.lines().map(|s| s.to_string()).collect())
 }
 
 #[cfg(target_arch = "wasm32")]
-async fn load_quote(lang: &str) -> Result<Vec<String>, String> {
-    use crate::languages::{language_from_str};
-    use crate::config::GameMode;
-
-    // Convert language string to QuotesLanguages enum
-    let language = match language_from_str(lang, GameMode::Quote) {
-        Language::Quotes(lang) => lang,
-        _ => return Err("invalid language for quote mode".to_string()),
-    };
+async fn fetch_quote(lang: &str, file: &str) -> Result<Quote, String> {
+    let url = format!("{}/assets/quotes/{}/{}", base_url(), lang, file);
+    let text = reqwest::get(&url)
+        .await
+        .map_err(|e| format!("failed to fetch quote: {}", e))?
+        .text()
+        .await
+        .map_err(|e| format!("failed to read response: {}", e))?;
+
+    Ok(Quote { code: is_code_pack(lang), ..Quote::parse(file, &text) })
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn load_quotes(lang: &str) -> Result<Vec<Quote>, String> {
+    let pack = find_pack(PackKind::Quotes, lang).ok_or_else(|| format!("unknown quotes language '{}'", lang))?;
+
+    let mut quotes = Vec::new();
+    for file in &pack.quote_files() {
+        quotes.push(fetch_quote(lang, file).await?);
+    }
+
+    Ok(quotes)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn load_quotes(lang: &str) -> Result<Vec<Quote>, String> {
+    let pack = find_pack(PackKind::Quotes, lang).ok_or_else(|| format!("unknown quotes language '{}'", lang))?;
+
+    // files come in stable order, so seeded choice is reproducible
+    pack.quote_files()
+        .iter()
+        .map(|file| pack.read_quote(file).map(|text| Quote { code: is_code_pack(lang), ..Quote::parse(file, &text) }))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("failed to read quote, {}", e))
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn load_quote(lang: &str, seed: Option<u64>, length: Option<QuoteLength>, id: Option<&str>) -> Result<Quote, String> {
+    let pack = find_pack(PackKind::Quotes, lang).ok_or_else(|| format!("unknown quotes language '{}'", lang))?;
 
     // Get list of quote files for the language
-    let quote_files = language.quote_files();
+    let quote_files = pack.quote_files();
     if quote_files.is_empty() {
         return Err("no quote files available for this language".to_string());
     }
 
-    // Select a random quote file
-    let mut rng = rng();
-    let selected_file = quote_files
-        .choose(&mut rng)
-        .ok_or_else(|| "failed to select quote file".to_string())?;
+    // length and id are known only from text, otherwise single quote is enough, same seed picks the same one
+    if length.is_none() && id.is_none() {
+        let mut rng = seeded_rng(seed);
+        let selected_file = quote_files
+            .choose(&mut rng)
+            .ok_or_else(|| "failed to select quote file".to_string())?;
 
-    // Construct URL and fetch the file
-    let url = format!("{}/assets/quotes/{}/{}", base_url(), lang, selected_file);
-    let text = reqwest::get(&url)
-        .await
-        .map_err(|e| format!("failed to fetch quote: {}", e))?
-        .text()
-        .await
-        .map_err(|e| format!("failed to read response: {}", e))?;
+        return fetch_quote(lang, selected_file).await;
+    }
 
-    Ok(text.lines().map(|s| s.to_string()).collect())
+    pick_quote(load_quotes(lang).await?, seed, length, id, lang)
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-fn load_quote(lang: &str) -> Result<Vec<String>, String> {
-    let dir = Path::new(QUOTES_DIR).join(lang);
-    let files = fs::read_dir(&dir)
-        .map_err(|e| format!("cannot read directory '{}': {}", dir.display(), e))?
-        .flatten()
-        .map(|e| e.path())
-        .collect::<Vec<_>>();
+fn load_quote(lang: &str, seed: Option<u64>, length: Option<QuoteLength>, id: Option<&str>) -> Result<Quote, String> {
+    let quotes = load_quotes(lang)?;
+    if quotes.is_empty() {
+        return Err(format!("no quote files in '{}'", lang));
+    }
 
-    let mut rng = rng();
-    let file = files
-        .choose(&mut rng)
-        .expect("non-empty file list ensured by check above");
+    pick_quote(quotes, seed, length, id, lang)
+}
 
-    let lines = load_file(file)
-        .map_err(|e| format!("failed to read quote '{}', {}", file.display(), e))?;
-    Ok(lines)
+#[cfg(not(target_arch = "wasm32"))]
+fn load_snippet(repo: &str, config: &Config) -> Result<Quote, String> {
+    let snippets = repo_snippets(repo, config.strip_comments)?;
+    if snippets.is_empty() {
+        return Err(format!("no code blocks in '{}'", repo));
+    }
+
+    pick_quote(snippets, content_seed(config), config.quote_length, config.quote_id.as_deref(), repo)
 }
 
-fn finalize_lines(lines: Vec<String>, config: &Config) -> Vec<String> {
-    let mut rng = rng();
+// quote of given id, otherwise random quote of given length, any length if none
+fn pick_quote(quotes: Vec<Quote>, seed: Option<u64>, length: Option<QuoteLength>, id: Option<&str>, lang: &str) -> Result<Quote, String> {
+    if let Some(id) = id {
+        return quotes
+            .into_iter()
+            .find(|quote| quote.id == id)
+            .ok_or_else(|| format!("no quote '{}' in '{}'", id, lang));
+    }
 
-    let base_words: Vec<String> = lines
+    let quotes = quotes
         .into_iter()
-        .flat_map(|l| l.split_whitespace().map(|s| s.to_string()).collect::<Vec<_>>())
-        .collect();
+        .filter(|quote| length.is_none_or(|length| quote.length() == length))
+        .collect::<Vec<_>>();
 
-    let mut result = Vec::new();
+    let mut rng = seeded_rng(seed);
+    quotes.choose(&mut rng).cloned().ok_or_else(|| match length {
+        Some(length) => format!("no {} quotes in '{}'", length.as_str(), lang),
+        None => format!("no quotes in '{}'", lang),
+    })
+}
 
-    for _ in 0..config.word_count {
-        let word = base_words.choose(&mut rng).cloned().unwrap_or_default();
+// api function, that builds test content from mistyped and slow words of finished test, every word repeated n times
+pub fn generate_drill(raw_results: &RawResults, repeat: usize) -> GeneratorResponse {
+    let words = missed_words(raw_results);
 
-        let mut final_word = word.clone();
+    if words.is_empty() {
+        return GeneratorResponse::with_info(Vec::new(), "no missed or slow words".to_string());
+    }
 
-        // punctuation
-        if config.punctuation && rng.random_bool(PUNCT_PROBABILITY) {
-            final_word.push_str(PUNCTS.choose(&mut rng).unwrap());
-        }
+    GeneratorResponse::plain(
+        words
+            .into_iter()
+            .flat_map(|w| std::iter::repeat_n(w, repeat))
+            .collect()
+    )
+}
 
-        result.push(final_word);
+// api function, that picks n most frequent mistyped letters as practice targets
+// errors are (character, count) pairs, e.g. from results or aggregated history
+pub fn weak_targets(errors: &[(char, usize)], n: usize) -> Vec<String> {
+    let mut errors = errors
+        .iter()
+        .filter(|(c, count)| c.is_alphabetic() && *count > 0)
+        .map(|(c, count)| (c.to_lowercase().to_string(), *count))
+        .collect::<Vec<_>>();
+    errors.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
 
-        // numbers
-        if config.numbers && rng.random_bool(NUMBER_PROBABILITY) {
-            let number = rng.random_range(NUMBER_RANGE);
-            result.push(number.to_string());
+    let mut targets: Vec<String> = Vec::new();
+    for (c, _) in errors {
+        if !targets.contains(&c) {
+            targets.push(c);
         }
     }
 
-    result
+    targets.truncate(n);
+    targets
 }
 
-fn split_lines(lines: Vec<String>) -> Vec<String> {
+fn finalize_lines(lines: Vec<String>, config: &Config) -> Vec<String> {
+    WordStream::new(lines, config).next_words(config.word_count)
+}
+
+pub(crate) fn split_lines(lines: Vec<String>) -> Vec<String> {
     let len = lines.len();
     let mut result = Vec::new();
 
@@ -270,9 +688,252 @@ fn split_lines(lines: Vec<String>) -> Vec<String> {
     result
 }
 
+// code lines as words: indentation is word of its own, tokens keep their symbols,
+// line break ends last token of line, blank lines add more breaks to it
+pub(crate) fn split_code(lines: Vec<String>) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+
+    for line in lines {
+        let line = line.trim_end();
+        let code = line.trim_start();
+
+        // blank lines before code are dropped
+        if code.is_empty() {
+            if let Some(last) = result.last_mut() {
+                last.push('\n');
+            }
+            continue;
+        }
+
+        let indent = &line[..line.len() - code.len()];
+        if !indent.is_empty() {
+            result.push(indent.to_string());
+        }
+
+        result.extend(code.split_whitespace().map(|w| w.to_string()));
+
+        if let Some(last) = result.last_mut() {
+            last.push('\n');
+        }
+    }
+
+    // test ends with last token, not with line break
+    if let Some(last) = result.last_mut() {
+        let len = last.trim_end_matches('\n').len();
+        last.truncate(len);
+    }
+
+    result
+}
+
+// text of file, or of standard input for '-'
+pub(crate) fn read_file(path: &str) -> io::Result<String> {
+    if path != STDIN_FILE {
+        return fs::read_to_string(path);
+    }
+
+    STDIN_TEXT
+        .get_or_init(|| {
+            let mut stdin = io::stdin();
+
+            // nothing is piped, reading would wait for end of input typed in terminal
+            if stdin.is_terminal() {
+                return Err((io::ErrorKind::InvalidInput, "nothing is piped to standard input".to_string()));
+            }
+
+            let mut text = String::new();
+            stdin.read_to_string(&mut text).map(|_| text).map_err(|e| (e.kind(), e.to_string()))
+        })
+        .clone()
+        .map_err(|(kind, msg)| io::Error::new(kind, msg))
+}
+
+// text of custom file without markup of its format
+pub(crate) fn load_text(path: &str, config: &Config) -> io::Result<String> {
+    let format = config.text_format.unwrap_or_else(|| TextFormat::of_file(path));
+    read_file(path).map(|text| clean_text(&text, format, config.ascii))
+}
+
+// lines of custom word list, plain one keeps its lines as they are for weights after tab
+fn load_word_list(path: &str, config: &Config) -> io::Result<Vec<String>> {
+    let text = match config.text_format.unwrap_or_else(|| TextFormat::of_file(path)) {
+        TextFormat::Plain if config.ascii => fold_ascii(&read_file(path)?),
+        TextFormat::Plain => read_file(path)?,
+        _ => load_text(path, config)?,
+    };
+    Ok(text.lines().map(|line| line.to_string()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use crate::{
+        clock::ManualClock,
+        config::validate_config,
+        results::Key,
+    };
+
+    #[test]
+    fn same_seed_gives_same_test() {
+        let words = validate_config(Config { seed: Some(11), punctuation: true, numbers: true, ..Config::default() }).payload;
+        assert_eq!(generate_content(&words).payload, generate_content(&words).payload);
+        assert_ne!(generate_content(&words).payload, generate_content(&Config { seed: Some(12), ..words }).payload);
+
+        let quote = validate_config(Config { mode: GameMode::Quote, language: Language::Quotes("en".to_string()), seed: Some(11), ..Config::default() }).payload;
+        let picked = generate_quote(&quote).payload.unwrap();
+        assert_eq!(generate_quote(&quote).payload, Some(picked));
+    }
+
+    #[test]
+    fn timed_test_gets_words_from_stream() {
+        let config = validate_config(Config { time_limit: Some(30), seed: Some(5), word_count: 12, ..Config::default() }).payload;
+        let content = generate_content(&config).payload;
+        let mut stream = generate_stream(&config).payload.expect("timed words test has stream");
+        let mut test = Test::new(content.clone(), &config);
+
+        // far from the end nothing is appended
+        assert!(stream.fill(&mut test).is_empty());
+
+        while test.current_word < 5 {
+            test.handle_key(Key::Space);
+            test.handle_key(Key::Char('x'));
+        }
+        let appended = stream.fill(&mut test);
+        assert_eq!(appended.len(), STREAM_CHUNK);
+        assert_eq!(test.words.len(), content.len() + STREAM_CHUNK);
+
+        // seeded stream is the same for everyone with the same config
+        let mut again = generate_stream(&config).payload.unwrap();
+        assert_eq!(again.next_words(STREAM_CHUNK), appended);
+
+        // words without time limit are not streamed
+        assert!(generate_stream(&Config { time_limit: None, ..config }).payload.is_none());
+    }
+
+    #[test]
+    fn drill_repeats_mistyped_words() {
+        let clock = ManualClock::new();
+        let config = validate_config(Config { seed: Some(4), ..Config::default() }).payload;
+        let words = ["one", "two", "three"].map(String::from).to_vec();
+        let mut test = Test::with_clock(words, &config, Box::new(clock.clone()));
+
+        // steady pace, one correction in 'two'
+        for c in "one twx\u{8}o three".chars() {
+            clock.advance(Duration::from_millis(150));
+            test.handle_key(match c {
+                ' ' => Key::Space,
+                '\u{8}' => Key::Backspace,
+                c => Key::Char(c),
+            });
+        }
+
+        assert!(test.complete);
+        assert_eq!(generate_drill(&RawResults::from(&test), 2).payload, vec!["two", "two"]);
+    }
+
+    #[test]
+    fn quote_id_picks_quote() {
+        let quotes = ["one", "two", "three"].map(|id| Quote::parse(id, id)).to_vec();
+
+        assert_eq!(pick_quote(quotes.clone(), Some(1), None, Some("two"), "en").unwrap().id, "two");
+        assert_eq!(pick_quote(quotes.clone(), None, None, Some("four"), "en").unwrap_err(), "no quote 'four' in 'en'");
+        assert!(pick_quote(quotes, None, Some(QuoteLength::Long), None, "en").is_err());
+    }
+
+    #[test]
+    fn code_keeps_indentation_and_line_breaks() {
+        let lines = ["", "fn main() {", "    let x = [1, 2];", "", "\tdbg!(x);  ", "}", ""].map(String::from).to_vec();
+
+        assert_eq!(split_code(lines), [
+            "fn", "main()", "{\n",
+            "    ", "let", "x", "=", "[1,", "2];\n\n",
+            "\t", "dbg!(x);\n",
+            "}",
+        ]);
+    }
+
+    #[test]
+    fn practice_stream_keeps_bias() {
+        let lines = ["zone", "tree", "mile", "cold", "paint", "shop"].iter().map(|w| w.to_string()).collect::<Vec<_>>();
+        let config = Config { seed: Some(1), time_limit: Some(30), ..Config::default() };
+        let targets = vec!["z".to_string()];
+
+        let biased = WordStream::after_content(lines.clone(), &config, &targets).next_words(100);
+        let plain = WordStream::after_content(lines, &config, &[]).next_words(100);
+        let hits = |words: &[String]| words.iter().filter(|w| w.contains('z')).count();
+
+        assert!(hits(&biased) > 60, "only {} practice words", hits(&biased));
+        assert!(hits(&plain) < 40);
+    }
 
-fn load_file<P: AsRef<Path>>(path: P) -> io::Result<Vec<String>> {
-    let file = fs::File::open(&path)?;
-    let reader = io::BufReader::new(file);
-    reader.lines().collect()
+    #[test]
+    fn top_words_limit_picks() {
+        let config = validate_config(Config { top_words: Some(5), word_count: 200, seed: Some(2), ..Config::default() }).payload;
+        let top = parse_word_list(load_words("en").unwrap()).into_iter().take(5).map(|(word, _)| word).collect::<Vec<_>>();
+
+        let words = generate_content(&config).payload;
+        assert!(words.iter().all(|word| top.contains(word)), "{:?}", words);
+    }
+
+    #[test]
+    fn unweighted_words_are_ranked() {
+        let lines = vec!["the of".to_string(), "rare\t0.5".to_string(), "and".to_string()];
+        let words = parse_word_list(lines);
+
+        assert_eq!(words, vec![
+            ("the".to_string(), 1.0),
+            ("of".to_string(), 0.5),
+            ("rare".to_string(), 0.5),
+            ("and".to_string(), 0.25),
+        ]);
+    }
+
+    #[test]
+    fn word_list_keeps_weights() {
+        let path = std::env::temp_dir().join(format!("typecrab-weights-{}.txt", std::process::id()));
+        fs::write(&path, "alpha\t100\nbeta\t1\n").unwrap();
+
+        let config = validate_config(Config {
+            file: Some(path.to_string_lossy().into_owned()),
+            weighted: true,
+            word_count: 50,
+            seed: Some(3),
+            ..Config::default()
+        }).payload;
+        let words = generate_content(&config).payload;
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(words.len(), 50);
+        assert!(words.iter().all(|w| w == "alpha" || w == "beta"), "{:?}", words);
+        assert!(words.iter().filter(|w| *w == "alpha").count() > 40);
+    }
+
+    #[test]
+    fn piped_text_is_read_once() {
+        // the only test reading standard input, as if it was piped
+        STDIN_TEXT.set(Ok("crab\nclaw\n".to_string())).unwrap();
+
+        let config = validate_config(Config {
+            file: Some(STDIN_FILE.to_string()),
+            word_count: 10,
+            ..Config::default()
+        }).payload;
+
+        // restart gets text again, although input was consumed
+        for _ in 0..2 {
+            let words = generate_content(&config).payload;
+            assert_eq!(words.len(), 10);
+            assert!(words.iter().all(|w| w == "crab" || w == "claw"), "{:?}", words);
+        }
+    }
+
+    #[test]
+    fn daily_text_comes_from_its_day() {
+        let config = validate_config(Config { daily: true, ..Config::default() }).payload;
+        let on = |day| generate_content(&Config { day: Some(day), ..config.clone() }).payload;
+
+        assert_eq!(on(20000), on(20000));
+        assert_ne!(on(20000), on(20001));
+    }
 }
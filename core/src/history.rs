@@ -0,0 +1,505 @@
+/*
+ * core/src/history.rs
+ */
+
+use std::collections::{
+    HashMap,
+    HashSet
+};
+use serde::{Serialize, Deserialize};
+
+use serde_json::Value;
+
+use crate::{
+    config::Config,
+    response::Response,
+    results::FinalResults,
+    schema::{
+        migrate_final_results,
+        version_of,
+        SCHEMA_VERSION
+    },
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+};
+
+pub type HistoryResponse = Response<Vec<HistoryRecord>>;
+
+// where test text came from, only regular tests are reproducible by config and count for bests
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TestKind {
+    #[default]
+    Regular,
+    Drill,      // missed words of previous test
+    Practice,   // words biased toward weak targets of history
+}
+
+// one finished test representation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryRecord {
+    #[serde(default)]
+    pub version: u32,               // schema version record was written with
+    pub timestamp: u64,             // unix time in seconds, when test was finished
+    pub config: Config,             // config test was run with
+    pub results: FinalResults,      // processed results
+    pub words: Vec<String>,         // test content
+    #[serde(default)]
+    pub daily: Option<u64>,         // day of daily challenge attempt, none for other tests
+    #[serde(default)]
+    pub kind: TestKind,
+}
+
+impl HistoryRecord {
+    // daily is the day challenge text was generated for, it may be finished on the next one
+    pub fn new(config: Config, results: FinalResults, words: Vec<String>, daily: Option<u64>) -> Self {
+        Self {
+            version: SCHEMA_VERSION,
+            timestamp: timestamp_now(),
+            daily: daily.filter(|_| config.daily),
+            config,
+            results,
+            words,
+            kind: TestKind::Regular,
+        }
+    }
+
+    // text of other kind is no daily attempt, drill text does not come from seed either
+    pub fn with_kind(mut self, kind: TestKind) -> Self {
+        if kind != TestKind::Regular {
+            self.daily = None;
+        }
+        if kind == TestKind::Drill {
+            self.config.seed = None;
+        }
+        self.kind = kind;
+        self
+    }
+}
+
+// averaged results over some set of records
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryAverage {
+    pub count: usize,
+    pub wpm: f64,
+    pub raw_wpm: f64,
+    pub accuracy: f64,
+    pub consistency: f64,
+}
+
+// storage backend for test history
+pub trait HistoryStore {
+    fn load(&self) -> HistoryResponse;
+    fn save(&mut self, record: &HistoryRecord) -> Response<()>;
+    fn clear(&mut self) -> Response<()>;
+}
+
+// current unix time in seconds
+pub fn timestamp_now() -> u64 {
+    #[cfg(target_arch = "wasm32")]
+    {
+        (js_sys::Date::now() / 1000.0) as u64
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0)
+    }
+}
+
+// api function, that returns last n records, newest first
+pub fn last_n(records: &[HistoryRecord], n: usize) -> Vec<&HistoryRecord> {
+    let mut sorted = records.iter().collect::<Vec<_>>();
+    sorted.sort_by_key(|r| std::cmp::Reverse(r.timestamp));
+    sorted.truncate(n);
+    sorted
+}
+
+// api function, that returns record with best wpm for every distinct config, seed is not taken into account
+pub fn best_per_config(records: &[HistoryRecord]) -> Vec<&HistoryRecord> {
+    best_by(regular(records), |r| Config { seed: None, ..r.config.clone() })
+}
+
+// api function, that returns best daily challenge attempt for every day, newest day first
+pub fn daily_bests(records: &[HistoryRecord]) -> Vec<&HistoryRecord> {
+    let mut bests = best_by(regular(records).filter(|r| r.daily.is_some()), |r| r.daily);
+    bests.sort_by_key(|r| std::cmp::Reverse(r.daily));
+    bests
+}
+
+// api function, that counts consecutive days with daily challenge attempts
+// streak is still alive if last attempt was yesterday
+pub fn daily_streak(records: &[HistoryRecord], today: u64) -> usize {
+    let days = records.iter().filter_map(|r| r.daily).collect::<HashSet<_>>();
+
+    let mut day = if days.contains(&today) { today } else { today.saturating_sub(1) };
+    let mut streak = 0;
+
+    while days.contains(&day) {
+        streak += 1;
+        if day == 0 {
+            break;
+        }
+        day -= 1;
+    }
+
+    streak
+}
+
+// api function, that returns record with best wpm for every mode/language/word count combination
+pub fn best_per_category(records: &[HistoryRecord]) -> Vec<&HistoryRecord> {
+    best_by(regular(records), |r| (r.config.mode, r.config.language.clone(), r.config.word_count))
+}
+
+// api function, that sums key errors over all records, most frequent first
+pub fn aggregate_errors(records: &[HistoryRecord]) -> Vec<(char, usize)> {
+    let mut counts: HashMap<char, usize> = HashMap::new();
+
+    for record in records {
+        for (c, count) in &record.results.errors {
+            *counts.entry(*c).or_insert(0) += count;
+        }
+    }
+
+    let mut errors = counts.into_iter().collect::<Vec<_>>();
+    errors.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    errors
+}
+
+// api function, that averages results of records finished within [from, to]
+pub fn average_over_period<'a>(records: impl IntoIterator<Item = &'a HistoryRecord>, from: u64, to: u64) -> Option<HistoryAverage> {
+    let period = records
+        .into_iter()
+        .filter(|r| r.timestamp >= from && r.timestamp <= to)
+        .collect::<Vec<_>>();
+
+    if period.is_empty() {
+        return None;
+    }
+
+    let count = period.len() as f64;
+    let mean = |f: fn(&FinalResults) -> f64| period.iter().map(|r| f(&r.results)).sum::<f64>() / count;
+
+    Some(HistoryAverage {
+        count: period.len(),
+        wpm: mean(|r| r.wpm),
+        raw_wpm: mean(|r| r.raw_wpm),
+        accuracy: mean(|r| r.accuracy),
+        consistency: mean(|r| r.consistency),
+    })
+}
+
+// records of regular tests, practice ones are no personal bests
+fn regular(records: &[HistoryRecord]) -> impl Iterator<Item = &HistoryRecord> {
+    records.iter().filter(|r| r.kind == TestKind::Regular)
+}
+
+// best wpm record for every distinct key, in order of first appearance
+fn best_by<'a, I, K, F>(records: I, key: F) -> Vec<&'a HistoryRecord>
+where
+    I: IntoIterator<Item = &'a HistoryRecord>,
+    K: PartialEq,
+    F: Fn(&HistoryRecord) -> K,
+{
+    let mut best: Vec<(K, &HistoryRecord)> = Vec::new();
+
+    for record in records {
+        let k = key(record);
+        match best.iter_mut().find(|(b, _)| *b == k) {
+            Some((_, current)) => {
+                if record.results.wpm > current.results.wpm {
+                    *current = record;
+                }
+            }
+            None => best.push((k, record)),
+        }
+    }
+
+    best.into_iter().map(|(_, r)| r).collect()
+}
+
+// parse records one per line, counting malformed ones
+fn parse_lines(content: &str) -> HistoryResponse {
+    let mut records = Vec::new();
+    let mut skipped = 0;
+
+    for line in content.lines().filter(|l| !l.trim().is_empty()) {
+        match parse_record(line) {
+            Ok(record) => records.push(record),
+            Err(_) => skipped += 1,
+        }
+    }
+
+    if skipped > 0 {
+        Response::with_warning(records, format!("skipped {} malformed history records", skipped))
+    } else {
+        Response::plain(records)
+    }
+}
+
+// parse one record, migrating results written by older schema
+fn parse_record(line: &str) -> Result<HistoryRecord, String> {
+    let mut value: Value = serde_json::from_str(line).map_err(|e| e.to_string())?;
+    let version = version_of(&value);
+
+    if let Some(results) = value.get_mut("results") {
+        *results = migrate_final_results(results.take(), version)?;
+    }
+    value["version"] = SCHEMA_VERSION.into();
+
+    serde_json::from_value(value).map_err(|e| e.to_string())
+}
+
+// jsonl file backend, one record per line
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone)]
+pub struct JsonlStore {
+    path: PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl JsonlStore {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self { path: path.into() }
+    }
+
+    // store in user data directory, e.g. ~/.local/share/typecrab/history.jsonl
+    pub fn open_default() -> Option<Self> {
+        crate::paths::data_dir().map(|dir| Self::new(dir.join("history.jsonl")))
+    }
+
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl HistoryStore for JsonlStore {
+    fn load(&self) -> HistoryResponse {
+        if !self.path.exists() {
+            return Response::plain(Vec::new());
+        }
+
+        match fs::read_to_string(&self.path) {
+            Ok(content) => parse_lines(&content),
+            Err(e) => Response::with_error(
+                Vec::new(),
+                format!("cannot read history '{}', {}", self.path.display(), e),
+            ),
+        }
+    }
+
+    fn save(&mut self, record: &HistoryRecord) -> Response<()> {
+        let line = match serde_json::to_string(record) {
+            Ok(line) => line,
+            Err(e) => return Response::with_error((), format!("cannot serialize record, {}", e)),
+        };
+
+        if let Some(parent) = self.path.parent() {
+            if let Err(e) = fs::create_dir_all(parent) {
+                return Response::with_error((), format!("cannot create directory '{}', {}", parent.display(), e));
+            }
+        }
+
+        let result = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| writeln!(file, "{}", line));
+
+        match result {
+            Ok(_) => Response::plain(()),
+            Err(e) => Response::with_error((), format!("cannot write history '{}', {}", self.path.display(), e)),
+        }
+    }
+
+    fn clear(&mut self) -> Response<()> {
+        if !self.path.exists() {
+            return Response::plain(());
+        }
+
+        match fs::remove_file(&self.path) {
+            Ok(_) => Response::plain(()),
+            Err(e) => Response::with_error((), format!("cannot remove history '{}', {}", self.path.display(), e)),
+        }
+    }
+}
+
+// browser local storage backend, records are kept in jsonl string under one key
+#[cfg(target_arch = "wasm32")]
+#[derive(Debug, Clone)]
+pub struct LocalStorageStore {
+    key: String,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl LocalStorageStore {
+    pub fn new<S: Into<String>>(key: S) -> Self {
+        Self { key: key.into() }
+    }
+
+    fn storage() -> Option<web_sys::Storage> {
+        web_sys::window()?.local_storage().ok()?
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Default for LocalStorageStore {
+    fn default() -> Self {
+        Self::new("history")
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl HistoryStore for LocalStorageStore {
+    fn load(&self) -> HistoryResponse {
+        let Some(storage) = Self::storage() else {
+            return Response::with_error(Vec::new(), "local storage is not available");
+        };
+
+        match storage.get_item(&self.key) {
+            Ok(Some(content)) => parse_lines(&content),
+            Ok(None) => Response::plain(Vec::new()),
+            Err(e) => Response::with_error(Vec::new(), format!("cannot read history, {:?}", e)),
+        }
+    }
+
+    fn save(&mut self, record: &HistoryRecord) -> Response<()> {
+        let Some(storage) = Self::storage() else {
+            return Response::with_error((), "local storage is not available");
+        };
+
+        let line = match serde_json::to_string(record) {
+            Ok(line) => line,
+            Err(e) => return Response::with_error((), format!("cannot serialize record, {}", e)),
+        };
+
+        let mut content = storage.get_item(&self.key).ok().flatten().unwrap_or_default();
+        content.push_str(&line);
+        content.push('\n');
+
+        match storage.set_item(&self.key, &content) {
+            Ok(_) => Response::plain(()),
+            Err(e) => Response::with_error((), format!("cannot write history, {:?}", e)),
+        }
+    }
+
+    fn clear(&mut self) -> Response<()> {
+        let Some(storage) = Self::storage() else {
+            return Response::with_error((), "local storage is not available");
+        };
+
+        match storage.remove_item(&self.key) {
+            Ok(_) => Response::plain(()),
+            Err(e) => Response::with_error((), format!("cannot clear history, {:?}", e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::validate_config,
+        response::Level,
+    };
+
+    #[test]
+    fn store_keeps_records_in_order() {
+        let path = std::env::temp_dir().join(format!("typecrab-history-{}.jsonl", std::process::id()));
+        let mut store = JsonlStore::new(&path);
+        let config = validate_config(Config { time_limit: Some(15), seed: Some(42), ..Config::default() }).payload;
+
+        for wpm in [61.0, 74.5] {
+            let results = FinalResults { wpm, ..FinalResults::default() };
+            assert!(store.save(&HistoryRecord::new(config.clone(), results, vec!["word".to_string()], None)).message.is_none());
+        }
+        fs::write(&path, fs::read_to_string(&path).unwrap() + "{not json\n").unwrap();
+
+        let response = store.load();
+        assert_eq!(response.payload.iter().map(|r| r.results.wpm).collect::<Vec<_>>(), vec![61.0, 74.5]);
+        assert_eq!(response.payload[0].config, config);
+        assert!(matches!(response.message, Some((Level::Warning, _))));
+
+        store.clear();
+        assert!(store.load().payload.is_empty());
+    }
+
+    #[test]
+    fn period_average_and_error_totals() {
+        let config = validate_config(Config::default()).payload;
+        let mut records = Vec::new();
+        for (timestamp, wpm, errors) in [(100, 40.0, vec![('e', 2), ('t', 1)]), (200, 60.0, vec![('t', 3)]), (300, 90.0, vec![('q', 1)])] {
+            let results = FinalResults { wpm, accuracy: 90.0, errors, ..FinalResults::default() };
+            records.push(HistoryRecord { timestamp, ..HistoryRecord::new(config.clone(), results, Vec::new(), None) });
+        }
+
+        let average = average_over_period(&records, 100, 200).unwrap();
+        assert_eq!((average.count, average.wpm, average.accuracy), (2, 50.0, 90.0));
+        assert_eq!(average_over_period(&records, 400, 500), None);
+
+        assert_eq!(aggregate_errors(&records), vec![('t', 4), ('e', 2), ('q', 1)]);
+        assert_eq!(last_n(&records, 2).iter().map(|r| r.timestamp).collect::<Vec<_>>(), vec![300, 200]);
+    }
+
+    #[test]
+    fn drill_is_no_personal_best() {
+        // drill of missed words after seeded test keeps its config otherwise
+        let config = validate_config(Config { seed: Some(7), ..Config::default() }).payload;
+        let slow = FinalResults { wpm: 50.0, ..FinalResults::default() };
+        let fast = FinalResults { wpm: 90.0, ..FinalResults::default() };
+        let records = vec![
+            HistoryRecord::new(config.clone(), slow, Vec::new(), None),
+            HistoryRecord::new(config, fast, Vec::new(), None).with_kind(TestKind::Drill),
+        ];
+
+        assert_eq!(best_per_category(&records).len(), 1);
+        assert_eq!(best_per_category(&records)[0].results.wpm, 50.0);
+        assert_eq!(best_per_config(&records)[0].results.wpm, 50.0);
+        assert_eq!(records[1].config.seed, None);
+    }
+
+    #[test]
+    fn practice_is_no_personal_best() {
+        // practice of weak keys started from results of daily challenge
+        let config = validate_config(Config { daily: true, ..Config::default() }).payload;
+        let daily = FinalResults { wpm: 50.0, ..FinalResults::default() };
+        let practice = FinalResults { wpm: 90.0, ..FinalResults::default() };
+        let records = vec![
+            HistoryRecord::new(config.clone(), daily, Vec::new(), Some(20000)),
+            HistoryRecord::new(config, practice, Vec::new(), Some(20000)).with_kind(TestKind::Practice),
+        ];
+
+        assert_eq!(best_per_category(&records)[0].results.wpm, 50.0);
+        assert_eq!(daily_bests(&records).len(), 1);
+        assert_eq!(daily_bests(&records)[0].results.wpm, 50.0);
+        assert_eq!(records[1].daily, None);
+    }
+
+    #[test]
+    fn daily_keeps_day_of_its_text() {
+        // text of day 20000, finished on any later day
+        let config = validate_config(Config { daily: true, ..Config::default() }).payload;
+        let records = vec![HistoryRecord::new(config, FinalResults::default(), Vec::new(), Some(20000))];
+
+        assert_eq!(records[0].daily, Some(20000));
+        assert_eq!(daily_bests(&records)[0].daily, Some(20000));
+        assert_eq!(daily_streak(&records, 20000), 1);
+        assert_eq!(daily_streak(&records, 20001), 1);
+        assert_eq!(daily_streak(&records, 20002), 0);
+    }
+
+    #[test]
+    fn daily_day_needs_daily_config() {
+        let record = HistoryRecord::new(Config::default(), FinalResults::default(), Vec::new(), Some(20000));
+
+        assert_eq!(record.daily, None);
+    }
+}
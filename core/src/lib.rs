@@ -6,17 +6,27 @@ mod config;
 mod generator;
 mod response;
 mod listing;
-mod languages;
+mod registry;
+mod quotes;
+mod markup;
+mod matching;
+mod book;
+#[cfg(not(target_arch = "wasm32"))]
+mod repo;
 mod test;
+mod clock;
+mod history;
+mod schema;
+mod replay;
+mod daily;
+#[cfg(not(target_arch = "wasm32"))]
+mod paths;
 pub mod results;
 
 
-const WORDS_DIR: &str = "resources/words";
-
+// resources next to working directory, they override built-in ones
 #[cfg(not(target_arch = "wasm32"))]
-const QUOTES_DIR: &str = "resources/quotes";
-
-pub const SCHEMES_DIR: &str = "resources/schemes";
+const RESOURCES_DIR: &str = "resources";
 
 pub use response::{
     Level,
@@ -28,30 +38,134 @@ pub use config::{
     Language,
     validate_config
 };
-pub use generator::generate_content;
+pub use generator::{
+    generate_content,
+    generate_quote,
+    list_quotes,
+    generate_stream,
+    generate_practice_stream,
+    generate_practice,
+    generate_drill,
+    weak_targets,
+    random_seed,
+    STDIN_FILE,
+    WordStream,
+    GeneratorResponse,
+    StreamResponse
+};
+pub use quotes::{
+    Quote,
+    QuoteLength,
+    QuoteResponse,
+    QuoteListResponse,
+    search_quotes
+};
+pub use markup::{
+    TextFormat,
+    clean_text
+};
+pub use book::{
+    Bookmark,
+    Passage,
+    PassageResponse,
+    book_hash,
+    passage_at
+};
+#[cfg(not(target_arch = "wasm32"))]
+pub use book::{
+    BookmarkStore,
+    generate_passage
+};
+#[cfg(not(target_arch = "wasm32"))]
+pub use repo::{
+    repo_snippets
+};
 pub use listing::{
     list_languages,
     list_schemes
 };
 pub use test::{
-    Test
+    Test,
+    EndReason
+};
+pub use clock::{
+    Clock,
+    SystemClock,
+    ManualClock
+};
+#[cfg(target_arch = "wasm32")]
+pub use clock::{
+    BrowserClock
 };
 pub use results::{
     Key,
     Event,
     Word,
     RawResults,
-    process_results
+    GraphPoint,
+    process_results,
+    missed_words
+};
+pub use schema::{
+    SCHEMA_VERSION,
+    Migration,
+    migrate_raw_results,
+    migrate_final_results,
+    migrate_recording
+};
+pub use replay::{
+    Recording,
+    Replay,
+    replay
+};
+pub use history::{
+    HistoryRecord,
+    HistoryAverage,
+    TestKind,
+    HistoryStore,
+    last_n,
+    best_per_config,
+    best_per_category,
+    aggregate_errors,
+    average_over_period,
+    daily_bests,
+    daily_streak,
+    timestamp_now
+};
+pub use daily::{
+    today,
+    day_of,
+    daily_seed,
+    date_string
+};
+#[cfg(not(target_arch = "wasm32"))]
+pub use history::{
+    JsonlStore
+};
+#[cfg(target_arch = "wasm32")]
+pub use history::{
+    LocalStorageStore
+};
+#[cfg(not(target_arch = "wasm32"))]
+pub use paths::{
+    data_dir,
+    config_dir
 };
-pub use languages::{
+pub use registry::{
     language_from_str,
-    QuotesLanguages,
-    WordsLanguages,
-    Schemes,
+    packs,
+    pack_names,
+    find_pack,
+    is_code_pack,
+    Pack,
+    PackKind,
+    PackSource,
+    DEFAULT_LANGUAGE,
+    DEFAULT_CODE_LANGUAGE
 };
 
 #[cfg(target_arch = "wasm32")]
-pub use languages::{
+pub use registry::{
     BASE_PATH
 };
 
@@ -2,51 +2,43 @@
  * core/src/listing.rs
  */
 
-use std::fs;
-use std::path::Path;
-
-use crate::{response::Response, SCHEMES_DIR, WORDS_DIR};
-
-pub type ListingResponse = Response<Vec<String>>;
-
-// api function, that lists available languages
-pub fn list_languages() -> ListingResponse {
-    let dir = Path::new(WORDS_DIR);
-
-    let Ok(entries) = fs::read_dir(dir) else {
-        return Response::with_error(Vec::new(), format!("cannot read directory '{}'", dir.display()));
-    };
-
-    let mut langs = entries
-        .flatten()
-        .filter_map(|e| e.path().file_stem()?.to_str().map(|s| s.to_string()))
-        .collect::<Vec<_>>();
+use crate::{
+    config::GameMode,
+    registry::{
+        is_code_pack,
+        pack_kind,
+        packs,
+        Pack,
+        PackKind
+    },
+    response::Response,
+};
+
+pub type ListingResponse = Response<Vec<Pack>>;
+
+// api function, that lists available languages of given mode, built-in and on disk
+pub fn list_languages(mode: GameMode) -> ListingResponse {
+    let mut langs = packs(pack_kind(mode));
+
+    // code mode has only packs of source snippets
+    if mode == GameMode::Code {
+        langs.retain(|pack| is_code_pack(&pack.name));
+    }
 
     if langs.is_empty() {
         Response::with_error(Vec::new(), "no languages found")
     } else {
-        langs.sort();
         Response::plain(langs)
     }
 }
 
-// api function, that lists available color schemes
+// api function, that lists available color schemes, built-in and on disk
 pub fn list_schemes() -> ListingResponse {
-    let dir = Path::new(SCHEMES_DIR);
-
-    let Ok(entries) = fs::read_dir(dir) else {
-        return Response::with_error(Vec::new(), format!("cannot read directory '{}'", dir.display()));
-    };
-
-    let mut langs = entries
-        .flatten()
-        .filter_map(|e| e.path().file_stem()?.to_str().map(|s| s.to_string()))
-        .collect::<Vec<_>>();
+    let schemes = packs(PackKind::Schemes);
 
-    if langs.is_empty() {
+    if schemes.is_empty() {
         Response::with_error(Vec::new(), "no color schemes found")
     } else {
-        langs.sort();
-        Response::plain(langs)
+        Response::plain(schemes)
     }
 }
@@ -0,0 +1,257 @@
+/*
+ * core/src/markup.rs
+ */
+
+use std::path::Path;
+use once_cell::sync::Lazy;
+use regex::{
+    Captures,
+    Regex
+};
+use serde::{
+    Serialize,
+    Deserialize
+};
+
+// markup of custom text file, it is stripped before test
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TextFormat {
+    Plain,
+    Markdown,
+    Html,
+}
+
+impl TextFormat {
+    pub const ALL: [TextFormat; 3] = [TextFormat::Plain, TextFormat::Markdown, TextFormat::Html];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TextFormat::Plain => "plain",
+            TextFormat::Markdown => "markdown",
+            TextFormat::Html => "html",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|format| format.as_str() == name)
+    }
+
+    // format by file extension, e.g. 'README.md', other files are plain text
+    pub fn of_file(path: &str) -> Self {
+        let extension = Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase());
+
+        match extension.as_deref() {
+            Some("md" | "markdown") => TextFormat::Markdown,
+            Some("html" | "htm" | "xhtml") => TextFormat::Html,
+            _ => TextFormat::Plain,
+        }
+    }
+}
+
+// typographic characters and their ascii replacements
+const ASCII_FOLDS: &[(char, &str)] = &[
+    ('\u{2018}', "'"), ('\u{2019}', "'"), ('\u{201a}', "'"), ('\u{201b}', "'"), ('\u{2032}', "'"),
+    ('\u{201c}', "\""), ('\u{201d}', "\""), ('\u{201e}', "\""), ('\u{201f}', "\""), ('\u{2033}', "\""),
+    ('\u{00ab}', "\""), ('\u{00bb}', "\""),
+    ('\u{2010}', "-"), ('\u{2011}', "-"), ('\u{2012}', "-"), ('\u{2013}', "-"), ('\u{2014}', "-"), ('\u{2015}', "-"), ('\u{2212}', "-"),
+    ('\u{2026}', "..."),
+    ('\u{2022}', "*"),
+    ('\u{00a0}', " "), ('\u{2009}', " "), ('\u{202f}', " "),
+    ('\u{fb01}', "fi"), ('\u{fb02}', "fl"),
+];
+
+// html entities, that are usual in text
+const HTML_ENTITIES: &[(&str, &str)] = &[
+    ("amp", "&"), ("lt", "<"), ("gt", ">"), ("quot", "\""), ("apos", "'"), ("nbsp", "\u{00a0}"),
+    ("ndash", "\u{2013}"), ("mdash", "\u{2014}"), ("hellip", "\u{2026}"),
+    ("lsquo", "\u{2018}"), ("rsquo", "\u{2019}"), ("ldquo", "\u{201c}"), ("rdquo", "\u{201d}"),
+    ("laquo", "\u{00ab}"), ("raquo", "\u{00bb}"), ("copy", "\u{00a9}"),
+];
+
+// markdown block syntax
+static MD_RULE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\s*([-*_]\s*){3,}$").unwrap());
+static MD_DEFINITION: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\s*\[[^\]]+\]:\s*\S+").unwrap());
+static MD_TABLE_SEPARATOR: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\s*\|?\s*:?-+:?\s*(\|\s*:?-+:?\s*)*\|?\s*$").unwrap());
+static MD_BLOCK_MARKS: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\s*(>\s*)*(#{1,6}\s+|[-*+]\s+|\d+[.)]\s+)?").unwrap());
+static MD_CLOSING_HASHES: Lazy<Regex> = Lazy::new(|| Regex::new(r"\s+#+\s*$").unwrap());
+
+// markdown inline syntax, star emphasis is checked for flanking separately
+static MD_LINK: Lazy<Regex> = Lazy::new(|| Regex::new(r"!?\[([^\]]*)\](\([^)]*\)|\[[^\]]*\])").unwrap());
+static MD_AUTOLINK: Lazy<Regex> = Lazy::new(|| Regex::new(r"<(https?://[^>]+)>").unwrap());
+static MD_STAR_EMPHASIS: Lazy<Regex> = Lazy::new(|| Regex::new(r"(\*\*|\*)([^\s*](?:[^*]*?[^\s*])?)(\*\*|\*)").unwrap());
+static MD_EMPHASIS: Lazy<Regex> = Lazy::new(|| Regex::new(r"(__|~~|\b_)([^*_~]+?)(__|~~|_\b)").unwrap());
+
+// html syntax
+static HTML_HIDDEN: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?is)<!--.*?-->|<(script|style|head)\b.*?</(script|style|head)\s*>").unwrap());
+static HTML_BLOCK: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)</?(p|div|br|hr|h[1-6]|li|ul|ol|tr|table|blockquote|pre|section|article|header|footer|title)\b[^>]*>").unwrap());
+static HTML_TAG: Lazy<Regex> = Lazy::new(|| Regex::new(r"</?[a-zA-Z][^>]*>").unwrap());
+static HTML_ENTITY: Lazy<Regex> = Lazy::new(|| Regex::new(r"&(#[0-9]+|#[xX][0-9a-fA-F]+|[a-zA-Z]+);").unwrap());
+
+// api function, that strips markup of text in given format and collapses whitespace,
+// paragraphs are kept apart by blank line, ascii folds typographic quotes, dashes and ellipses
+pub fn clean_text(text: &str, format: TextFormat, ascii: bool) -> String {
+    let text = text.replace("\r\n", "\n");
+
+    let text = match format {
+        TextFormat::Plain => text,
+        TextFormat::Markdown => strip_markdown(&text),
+        TextFormat::Html => strip_html(&text),
+    };
+
+    let text = if ascii { fold_ascii(&text) } else { text };
+
+    collapse_whitespace(&text)
+}
+
+// line of single words, one blank line between paragraphs, none around text
+fn collapse_whitespace(text: &str) -> String {
+    let mut lines: Vec<String> = Vec::new();
+
+    for line in text.lines() {
+        let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !line.is_empty() || lines.last().is_some_and(|last| !last.is_empty()) {
+            lines.push(line);
+        }
+    }
+
+    while lines.last().is_some_and(|last| last.is_empty()) {
+        lines.pop();
+    }
+
+    lines.join("\n")
+}
+
+// typographic characters replaced by ascii ones, e.g. en dash becomes '-'
+pub(crate) fn fold_ascii(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match ASCII_FOLDS.iter().find(|(from, _)| *from == c) {
+            Some((_, to)) => result.push_str(to),
+            None => result.push(c),
+        }
+    }
+
+    result
+}
+
+// markdown as its text: code blocks, rules and link definitions are dropped,
+// lines of paragraph are joined, marks of headings, lists, quotes and emphasis are removed
+fn strip_markdown(text: &str) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    let mut fence: Option<&str> = None;
+    let mut paragraph = false;
+
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+
+        // fenced code is not prose
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            continue;
+        }
+        if let Some(marker) = ["```", "~~~"].into_iter().find(|marker| trimmed.starts_with(marker)) {
+            fence = Some(marker);
+            paragraph = false;
+            continue;
+        }
+
+        if line.trim().is_empty() || MD_RULE.is_match(line) || MD_DEFINITION.is_match(line) || MD_TABLE_SEPARATOR.is_match(line) {
+            lines.push(String::new());
+            paragraph = false;
+            continue;
+        }
+
+        // headings, list items and table rows stand on their own lines
+        let marks = MD_BLOCK_MARKS.find(line).map_or(0, |m| m.end());
+        let own_line = trimmed.starts_with(['#', '|']) || marks > line.len() - trimmed.len() && !trimmed.starts_with('>');
+
+        let content = MD_CLOSING_HASHES.replace(&line[marks..], "");
+        let content = strip_inline_markdown(content.trim_matches('|')).replace('|', " ");
+
+        match lines.last_mut() {
+            Some(last) if paragraph && !own_line => {
+                last.push(' ');
+                last.push_str(&content);
+            }
+            _ => lines.push(content),
+        }
+        paragraph = !trimmed.starts_with('#');
+    }
+
+    lines.join("\n")
+}
+
+// text of inline markup: links and images become their text, emphasis and code marks are removed
+fn strip_inline_markdown(text: &str) -> String {
+    let text = MD_LINK.replace_all(text, "$1");
+    let text = MD_AUTOLINK.replace_all(&text, "$1");
+    let text = strip_html(&text);
+
+    // star is emphasis only if it is not inside word, e.g. '2*3*4' is kept
+    let text = MD_STAR_EMPHASIS.replace_all(&text, |caps: &Captures| {
+        let whole = caps.get(0).unwrap();
+        let before = text[..whole.start()].chars().next_back();
+        let after = text[whole.end()..].chars().next();
+        let outside = |c: Option<char>| c.is_none_or(|c| !c.is_alphanumeric() && c != '_');
+
+        if caps[1] == caps[3] && outside(before) && outside(after) {
+            caps[2].to_string()
+        } else {
+            caps[0].to_string()
+        }
+    });
+    let text = MD_EMPHASIS.replace_all(&text, "$2");
+
+    text.replace('`', "")
+}
+
+// text of html: script, style and head are dropped, block elements start new lines, entities are decoded
+fn strip_html(text: &str) -> String {
+    // line breaks of source are only spaces, block elements make paragraphs
+    let text = HTML_HIDDEN.replace_all(text, "");
+    let text = text.replace('\n', " ");
+    let text = HTML_BLOCK.replace_all(&text, "\n\n");
+    let text = HTML_TAG.replace_all(&text, "");
+
+    HTML_ENTITY
+        .replace_all(&text, |caps: &Captures| {
+            let name = &caps[1];
+            let code = if let Some(hex) = name.strip_prefix("#x").or(name.strip_prefix("#X")) {
+                u32::from_str_radix(hex, 16).ok()
+            } else {
+                name.strip_prefix('#').and_then(|dec| dec.parse().ok())
+            };
+
+            match code {
+                Some(code) => char::from_u32(code).map(String::from).unwrap_or_default(),
+                None => HTML_ENTITIES
+                    .iter()
+                    .find(|(entity, _)| *entity == name)
+                    .map_or(caps[0].to_string(), |(_, c)| c.to_string()),
+            }
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn star_inside_words_is_kept() {
+        assert_eq!(clean_text("2*3*4", TextFormat::Markdown, false), "2*3*4");
+        assert_eq!(clean_text("see *.rs and char *p", TextFormat::Markdown, false), "see *.rs and char *p");
+    }
+
+    #[test]
+    fn star_emphasis_is_stripped() {
+        assert_eq!(clean_text("some **bold** and *italic* text", TextFormat::Markdown, false), "some bold and italic text");
+        assert_eq!(clean_text("*a* *b*", TextFormat::Markdown, false), "a b");
+    }
+}
@@ -0,0 +1,90 @@
+/*
+ * core/src/matching.rs
+ */
+
+use unicode_normalization::{
+    char::is_combining_mark,
+    UnicodeNormalization
+};
+
+// letters with stroke or other marks, that do not decompose to base letter and accent
+const LETTER_FOLDS: &[(char, char)] = &[
+    ('ł', 'l'), ('Ł', 'L'), ('ø', 'o'), ('Ø', 'O'), ('đ', 'd'), ('Đ', 'D'),
+    ('ħ', 'h'), ('Ħ', 'H'), ('ı', 'i'), ('ŧ', 't'), ('Ŧ', 'T'),
+];
+
+// how typed text matches beginning of expected one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Match {
+    Exact,
+    Accents,    // only accents differ, e.g. 'a' typed for 'á'
+    Wrong,
+}
+
+// composed form of text, e.g. 'a' with combining acute becomes 'á',
+// so text and input of dead keys or ime compare the same
+pub(crate) fn normalize(text: &str) -> String {
+    text.nfc().collect()
+}
+
+// combining accents alone, e.g. second half of decomposed input of dead key
+pub(crate) fn is_accent(typed: &str) -> bool {
+    !typed.is_empty() && typed.chars().all(is_combining_mark)
+}
+
+// match of typed text as prefix of expected one, both are normalized
+pub(crate) fn match_prefix(text: &str, typed: &str) -> Match {
+    if text.starts_with(typed) {
+        Match::Exact
+    } else if lenient_prefix(text, typed).is_some() {
+        Match::Accents
+    } else {
+        Match::Wrong
+    }
+}
+
+// shortest beginning of expected text, that matches typed one without accents
+pub(crate) fn lenient_prefix<'a>(text: &'a str, typed: &str) -> Option<&'a str> {
+    let target = fold(typed);
+    let mut folded = String::new();
+
+    for (i, c) in text.char_indices() {
+        if folded == target {
+            return Some(&text[..i]);
+        }
+        folded.extend(fold_char(c));
+        if !target.starts_with(&folded) {
+            return None;
+        }
+    }
+
+    (folded == target).then_some(text)
+}
+
+// text without accents, e.g. 'čaj' becomes 'caj'
+fn fold(text: &str) -> String {
+    text.chars().flat_map(fold_char).collect()
+}
+
+// base letters of character, combining marks alone fold to nothing
+fn fold_char(c: char) -> impl Iterator<Item = char> {
+    let c = LETTER_FOLDS.iter().find(|(from, _)| *from == c).map_or(c, |(_, to)| *to);
+    c.nfd().filter(|c| !is_combining_mark(*c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accents_alone_differ() {
+        assert_eq!(match_prefix("čaj", "ča"), Match::Exact);
+        assert_eq!(match_prefix("čaj", "ca"), Match::Accents);
+        assert_eq!(match_prefix("łódź", "lodz"), Match::Accents);
+        assert_eq!(match_prefix("čaj", "co"), Match::Wrong);
+
+        assert_eq!(lenient_prefix("naïve", "nai"), Some("naï"));
+        assert_eq!(normalize("e\u{301}"), "é");
+        assert!(is_accent("\u{301}") && !is_accent("e\u{301}"));
+    }
+}
@@ -0,0 +1,15 @@
+/*
+ * core/src/paths.rs
+ */
+
+use std::path::PathBuf;
+
+// api function, that returns per-user data directory (e.g. ~/.local/share/typecrab)
+pub fn data_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("typecrab"))
+}
+
+// api function, that returns per-user config directory (e.g. ~/.config/typecrab)
+pub fn config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("typecrab"))
+}
@@ -0,0 +1,232 @@
+/*
+ * core/src/quotes.rs
+ */
+
+use std::path::Path;
+use serde::{
+    Serialize,
+    Deserialize
+};
+
+use crate::{
+    generator::{
+        split_code,
+        split_lines
+    },
+    response::Response
+};
+
+pub type QuoteResponse = Response<Option<Quote>>;
+pub type QuoteListResponse = Response<Vec<Quote>>;
+
+// front-matter header starts and ends with this line
+const HEADER_DELIMITER: &str = "---";
+
+// quote length by characters, upper bounds of buckets
+const SHORT_MAX: usize = 100;
+const MEDIUM_MAX: usize = 300;
+const LONG_MAX: usize = 600;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QuoteLength {
+    Short,
+    Medium,
+    Long,
+    Thicc,
+}
+
+impl QuoteLength {
+    pub const ALL: [QuoteLength; 4] = [QuoteLength::Short, QuoteLength::Medium, QuoteLength::Long, QuoteLength::Thicc];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuoteLength::Short => "short",
+            QuoteLength::Medium => "medium",
+            QuoteLength::Long => "long",
+            QuoteLength::Thicc => "thicc",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|length| length.as_str() == name)
+    }
+
+    // bucket of text with given number of characters
+    pub fn of(chars: usize) -> Self {
+        if chars <= SHORT_MAX {
+            QuoteLength::Short
+        } else if chars <= MEDIUM_MAX {
+            QuoteLength::Medium
+        } else if chars <= LONG_MAX {
+            QuoteLength::Long
+        } else {
+            QuoteLength::Thicc
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Quote {
+    pub id: String,
+    pub author: Option<String>,
+    pub source: Option<String>,
+    pub lines: Vec<String>,
+    #[serde(default)]
+    pub code: bool,     // code snippet, typed with its indentation and line breaks
+}
+
+impl Quote {
+    // quote file is text with optional front-matter header, e.g.
+    // ---
+    // id: linus-open-source
+    // author: Linus Torvalds
+    // source: interview
+    // ---
+    // id defaults to file name without extension, unknown keys are ignored
+    pub fn parse(file: &str, content: &str) -> Self {
+        let id = Path::new(file)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or(file)
+            .to_string();
+
+        let mut quote = Quote { id, author: None, source: None, lines: Vec::new(), code: false };
+        let lines = content.lines().collect::<Vec<_>>();
+
+        // header without closing delimiter is part of text
+        let closing = match lines.first() {
+            Some(first) if first.trim_end() == HEADER_DELIMITER => lines
+                .iter()
+                .skip(1)
+                .position(|line| line.trim_end() == HEADER_DELIMITER)
+                .map(|i| i + 1),
+            _ => None,
+        };
+
+        let body = match closing {
+            Some(closing) => {
+                for line in &lines[1..closing] {
+                    let Some((key, value)) = line.split_once(':') else {
+                        continue;
+                    };
+                    let value = value.trim().to_string();
+                    if value.is_empty() {
+                        continue;
+                    }
+
+                    match key.trim() {
+                        "id" => quote.id = value,
+                        "author" => quote.author = Some(value),
+                        "source" => quote.source = Some(value),
+                        _ => {}
+                    }
+                }
+                &lines[closing + 1..]
+            }
+            None => &lines[..],
+        };
+
+        // blank lines around text, e.g. after header
+        let start = body.iter().position(|line| !line.trim().is_empty()).unwrap_or(body.len());
+        let end = body.iter().rposition(|line| !line.trim().is_empty()).map_or(start, |i| i + 1);
+
+        quote.lines = body[start..end].iter().map(|line| line.to_string()).collect();
+        quote
+    }
+
+    // test content, line breaks are kept at the end of words
+    pub fn words(&self) -> Vec<String> {
+        split_lines(self.lines.clone())
+    }
+
+    // test content of code mode, indentation and blank lines are kept too
+    pub fn code_words(&self) -> Vec<String> {
+        split_code(self.lines.clone())
+    }
+
+    // bucket by characters typed in test, code types line breaks and indentation instead of spaces
+    pub fn length(&self) -> QuoteLength {
+        if !self.code {
+            return QuoteLength::of(self.words().join(" ").chars().count());
+        }
+
+        let tokens = self.code_words();
+        let spaces = tokens.iter().filter(|token| !token.ends_with('\n') && !token.trim().is_empty()).count();
+        QuoteLength::of(tokens.iter().map(|token| token.chars().count()).sum::<usize>() + spaces.saturating_sub(1))
+    }
+
+    // term found in id, author, source or text, or name of quote length, case is ignored
+    fn matches(&self, term: &str) -> bool {
+        let term = term.to_lowercase();
+        let found = |field: &str| field.to_lowercase().contains(&term);
+
+        self.length().as_str() == term
+            || found(&self.id)
+            || self.author.as_deref().is_some_and(found)
+            || self.source.as_deref().is_some_and(found)
+            || self.lines.iter().any(|line| found(line))
+    }
+
+    // author and source as one line, none if quote has neither
+    pub fn attribution(&self) -> Option<String> {
+        match (&self.author, &self.source) {
+            (Some(author), Some(source)) => Some(format!("{}, {}", author, source)),
+            (Some(author), None) => Some(author.clone()),
+            (None, Some(source)) => Some(source.clone()),
+            (None, None) => None,
+        }
+    }
+}
+
+// api function, that searches quotes, e.g. from list_quotes
+// every word of query has to match id, author, source, text or length, empty query matches all quotes
+pub fn search_quotes(quotes: &[Quote], query: &str) -> Vec<Quote> {
+    quotes
+        .iter()
+        .filter(|quote| query.split_whitespace().all(|term| quote.matches(term)))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn code_length_counts_indentation() {
+        let text = "fn main() {\n    for i in 0..10 {\n        if i % 2 == 0 {\n            println!(\"{}\", i);\n        }\n    }\n}\n";
+        let prose = Quote::parse("even.rs", text);
+        let code = Quote { code: true, ..prose.clone() };
+
+        assert_eq!(prose.length(), QuoteLength::Short);
+        assert_eq!(code.length(), QuoteLength::Medium);
+    }
+
+    #[test]
+    fn header_gives_metadata() {
+        let quote = Quote::parse("misc/talk.txt", "---\nauthor: Linus Torvalds\nsource:\nmood: grumpy\n---\n\nTalk is cheap.\nShow me the code.\n\n");
+
+        assert_eq!(quote.id, "talk");
+        assert_eq!(quote.attribution().as_deref(), Some("Linus Torvalds"));
+        assert_eq!(quote.lines, ["Talk is cheap.", "Show me the code."]);
+        assert_eq!(quote.length(), QuoteLength::Short);
+
+        // unclosed header is text
+        assert_eq!(Quote::parse("a.txt", "---\nid: b").lines, ["---", "id: b"]);
+    }
+
+    #[test]
+    fn every_search_term_has_to_match() {
+        let quotes = [
+            Quote::parse("a.txt", "---\nauthor: Ada Lovelace\n---\nThe engine weaves patterns."),
+            Quote::parse("b.txt", "---\nauthor: Alan Kay\n---\nThe best way to predict the future is to invent it."),
+        ];
+        let ids = |query| search_quotes(&quotes, query).into_iter().map(|quote| quote.id).collect::<Vec<_>>();
+
+        assert_eq!(ids(""), ["a", "b"]);
+        assert_eq!(ids("the"), ["a", "b"]);
+        assert_eq!(ids("THE kay"), ["b"]);
+        assert_eq!(ids("short engine"), ["a"]);
+        assert!(ids("kay engine").is_empty());
+    }
+}
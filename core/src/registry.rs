@@ -0,0 +1,308 @@
+/*
+ * core/src/registry.rs
+ */
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+    fs,
+    path::{
+        Path,
+        PathBuf
+    },
+};
+
+use crate::config::{
+    GameMode,
+    Language
+};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{
+    paths::data_dir,
+    RESOURCES_DIR,
+};
+
+// built-in catalog generated by build script from resources directory, native builds embed contents too
+include!(concat!(env!("OUT_DIR"), "/builtin.rs"));
+
+pub const DEFAULT_LANGUAGE: &str = "en";
+pub const DEFAULT_CODE_LANGUAGE: &str = "code-rust";
+
+// quotes packs of source snippets for code mode are named like 'code-rust'
+const CODE_PACK_PREFIX: &str = "code-";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackKind {
+    Words,      // word list, 'name.txt'
+    Quotes,     // directory of quotes, 'name/*.txt'
+    Schemes,    // color scheme, 'name.css'
+}
+
+impl PackKind {
+    // directory name, both in resources and in user data directory
+    pub fn dir_name(&self) -> &'static str {
+        match self {
+            PackKind::Words => "words",
+            PackKind::Quotes => "quotes",
+            PackKind::Schemes => "schemes",
+        }
+    }
+
+    // extension of pack file, quotes pack is directory
+    #[cfg(not(target_arch = "wasm32"))]
+    fn extension(&self) -> Option<&'static str> {
+        match self {
+            PackKind::Words => Some("txt"),
+            PackKind::Quotes => None,
+            PackKind::Schemes => Some("css"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackSource {
+    BuiltIn,
+    #[cfg(not(target_arch = "wasm32"))]
+    File(PathBuf),      // file or quotes directory on disk
+}
+
+impl PackSource {
+    // human readable origin, e.g. for listing
+    pub fn location(&self) -> String {
+        match self {
+            PackSource::BuiltIn => "built-in".to_string(),
+            #[cfg(not(target_arch = "wasm32"))]
+            PackSource::File(path) => path.display().to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pack {
+    pub name: String,
+    pub kind: PackKind,
+    pub source: PackSource,
+}
+
+impl Pack {
+    // contents of word list or scheme
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn read(&self) -> Result<String, String> {
+        let file = match self.kind.extension() {
+            Some(extension) => format!("{}.{}", self.name, extension),
+            None => return Err(format!("'{}' is directory of quotes", self.name)),
+        };
+
+        match &self.source {
+            PackSource::BuiltIn => builtin_file(&format!("{}/{}", self.kind.dir_name(), file)),
+            PackSource::File(path) => fs::read_to_string(path)
+                .map_err(|e| format!("cannot read '{}', {}", path.display(), e)),
+        }
+    }
+
+    // quote file names of quotes pack in stable order, so seeded choice is reproducible
+    pub fn quote_files(&self) -> Vec<String> {
+        match &self.source {
+            PackSource::BuiltIn => BUILTIN_QUOTES
+                .iter()
+                .find(|(lang, _)| *lang == self.name)
+                .map(|(_, files)| files.iter().map(|file| file.to_string()).collect())
+                .unwrap_or_default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            PackSource::File(dir) => {
+                let mut files = dir_entries(dir, Some("txt"))
+                    .into_iter()
+                    .filter_map(|path| Some(path.file_name()?.to_str()?.to_string()))
+                    .collect::<Vec<_>>();
+                files.sort();
+                files
+            }
+        }
+    }
+
+    // contents of one quote file of quotes pack
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn read_quote(&self, file: &str) -> Result<String, String> {
+        match &self.source {
+            PackSource::BuiltIn => builtin_file(&format!("quotes/{}/{}", self.name, file)),
+            PackSource::File(dir) => {
+                let path = dir.join(file);
+                fs::read_to_string(&path).map_err(|e| format!("cannot read '{}', {}", path.display(), e))
+            }
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn builtin_file(file: &str) -> Result<String, String> {
+    BUILTIN_FILES
+        .iter()
+        .find(|(name, _)| *name == file)
+        .map(|(_, content)| content.to_string())
+        .ok_or_else(|| format!("no built-in file '{}'", file))
+}
+
+fn builtin_packs(kind: PackKind) -> Vec<Pack> {
+    let names: Vec<&str> = match kind {
+        PackKind::Words => BUILTIN_WORDS.to_vec(),
+        PackKind::Quotes => BUILTIN_QUOTES.iter().map(|(name, _)| *name).collect(),
+        PackKind::Schemes => BUILTIN_SCHEMES.to_vec(),
+    };
+
+    names
+        .into_iter()
+        .map(|name| Pack { name: name.to_string(), kind, source: PackSource::BuiltIn })
+        .collect()
+}
+
+// files with given extension or directories in directory, empty if it does not exist
+#[cfg(not(target_arch = "wasm32"))]
+fn dir_entries(dir: &Path, extension: Option<&str>) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|path| match extension {
+            Some(extension) => path.is_file() && path.extension().and_then(|s| s.to_str()) == Some(extension),
+            None => path.is_dir(),
+        })
+        .collect()
+}
+
+// packs on disk, found on every call, so new ones need no rebuild
+#[cfg(not(target_arch = "wasm32"))]
+fn dir_packs(base: &Path, kind: PackKind) -> Vec<Pack> {
+    dir_entries(&base.join(kind.dir_name()), kind.extension())
+        .into_iter()
+        .filter_map(|path| {
+            let name = match kind.extension() {
+                Some(_) => path.file_stem()?.to_str()?.to_string(),
+                None => path.file_name()?.to_str()?.to_string(),
+            };
+            Some(Pack { name, kind, source: PackSource::File(path) })
+        })
+        .collect()
+}
+
+// layers from the most preferred one: user data directory, resources in working directory, built-in
+#[cfg(not(target_arch = "wasm32"))]
+fn layers(kind: PackKind) -> Vec<Vec<Pack>> {
+    let mut layers = Vec::new();
+
+    if let Some(dir) = data_dir() {
+        layers.push(dir_packs(&dir, kind));
+    }
+    layers.push(dir_packs(Path::new(RESOURCES_DIR), kind));
+    layers.push(builtin_packs(kind));
+
+    layers
+}
+
+#[cfg(target_arch = "wasm32")]
+fn layers(kind: PackKind) -> Vec<Vec<Pack>> {
+    vec![builtin_packs(kind)]
+}
+
+// api function, that returns available packs of given kind sorted by name
+// pack on disk replaces built-in pack of the same name
+pub fn packs(kind: PackKind) -> Vec<Pack> {
+    let mut packs: Vec<Pack> = Vec::new();
+
+    for pack in layers(kind).into_iter().flatten() {
+        if !packs.iter().any(|p| p.name == pack.name) {
+            packs.push(pack);
+        }
+    }
+
+    packs.sort_by(|a, b| a.name.cmp(&b.name));
+    packs
+}
+
+// api function, that returns names of all packs of given kind
+pub fn pack_names(kind: PackKind) -> Vec<String> {
+    packs(kind).into_iter().map(|pack| pack.name).collect()
+}
+
+// api function, that finds pack by name
+pub fn find_pack(kind: PackKind, name: &str) -> Option<Pack> {
+    packs(kind).into_iter().find(|pack| pack.name == name)
+}
+
+// api function, that tells if quotes pack has source snippets for code mode
+pub fn is_code_pack(name: &str) -> bool {
+    name.starts_with(CODE_PACK_PREFIX)
+}
+
+// kind of packs, which languages of given mode are
+pub(crate) fn pack_kind(mode: GameMode) -> PackKind {
+    match mode {
+        GameMode::Quote | GameMode::Code => PackKind::Quotes,
+        GameMode::Words | GameMode::Zen | GameMode::Book => PackKind::Words,
+    }
+}
+
+// api function, that converts language name and game mode to language
+// unknown language falls back to default words language, or to default code language in code mode
+pub fn language_from_str(lang: &str, mode: GameMode) -> Language {
+    let kind = pack_kind(mode);
+
+    if mode == GameMode::Code {
+        let name = Some(lang).filter(|lang| is_code_pack(lang) && find_pack(kind, lang).is_some());
+        return Language::Quotes(name.unwrap_or(DEFAULT_CODE_LANGUAGE).to_string());
+    }
+
+    match (find_pack(kind, lang), kind) {
+        (Some(pack), PackKind::Quotes) => Language::Quotes(pack.name),
+        (Some(pack), _) => Language::Words(pack.name),
+        (None, _) => Language::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_language_falls_back_by_mode() {
+        assert_eq!(language_from_str("en", GameMode::Words), Language::Words("en".into()));
+        assert_eq!(language_from_str("code-rust", GameMode::Quote), Language::Quotes("code-rust".into()));
+        assert_eq!(language_from_str("klingon", GameMode::Words), Language::default());
+        // prose pack is no code language
+        assert_eq!(language_from_str("en", GameMode::Code), Language::Quotes(DEFAULT_CODE_LANGUAGE.into()));
+    }
+
+    #[test]
+    fn builtin_packs_are_embedded() {
+        for kind in [PackKind::Words, PackKind::Schemes] {
+            for pack in builtin_packs(kind) {
+                assert!(!pack.read().unwrap().is_empty(), "{} is empty", pack.name);
+            }
+        }
+
+        let quotes = builtin_packs(PackKind::Quotes);
+        assert!(quotes.iter().any(|pack| pack.name == DEFAULT_CODE_LANGUAGE));
+        for pack in quotes {
+            let files = pack.quote_files();
+            assert!(!files.is_empty(), "{} has no quotes", pack.name);
+            assert!(pack.read_quote(&files[0]).is_ok());
+        }
+    }
+
+    #[test]
+    fn pack_on_disk_is_found_without_rebuild() {
+        let base = std::env::temp_dir().join(format!("typecrab-packs-{}", std::process::id()));
+        fs::create_dir_all(base.join("words")).unwrap();
+        fs::write(base.join("words/pirate.txt"), "arr\nahoy\n").unwrap();
+        fs::write(base.join("words/notes.md"), "no pack").unwrap();
+
+        let packs = dir_packs(&base, PackKind::Words);
+        let content = packs.first().map(|pack| pack.read());
+        fs::remove_dir_all(&base).unwrap();
+
+        assert_eq!(packs.len(), 1);
+        assert_eq!(packs[0].name, "pirate");
+        assert_eq!(content, Some(Ok("arr\nahoy\n".to_string())));
+    }
+}
@@ -0,0 +1,212 @@
+/*
+ * core/src/replay.rs
+ */
+
+use std::time::Duration;
+use serde::{Serialize, Deserialize};
+
+use crate::{
+    config::Config,
+    response::Response,
+    results::{
+        Event,
+        RawResults
+    },
+    schema::{
+        from_versioned_json,
+        migrate_recording,
+        to_versioned_json
+    },
+    test::Test,
+};
+
+pub type ReplayResponse = Response<RawResults>;
+
+// recorded test: everything needed to rebuild it key by key
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Recording {
+    pub config: Config,
+    pub words: Vec<String>,     // content test was generated with
+    pub events: Vec<Event>,     // all events in order they happened
+}
+
+impl Recording {
+    pub fn new(config: Config, words: Vec<String>, raw_results: &RawResults) -> Self {
+        Self {
+            config,
+            words,
+            events: raw_results.events.clone(),
+        }
+    }
+
+    pub fn to_json_string(&self) -> Result<String, serde_json::Error> {
+        to_versioned_json(self)
+    }
+
+    pub fn from_json_string(json: &str) -> Result<Self, serde_json::Error> {
+        from_versioned_json(json, migrate_recording)
+    }
+}
+
+// step by step replay of recorded events through a fresh test
+#[derive(Debug)]
+pub struct Replay {
+    pub test: Test,
+    events: Vec<Event>,
+    next: usize,
+}
+
+impl Replay {
+    pub fn new(words: Vec<String>, config: &Config, events: Vec<Event>) -> Self {
+        Self {
+            test: Test::new(words, config),
+            events,
+            next: 0,
+        }
+    }
+
+    pub fn from_recording(recording: &Recording) -> Self {
+        Self::new(recording.words.clone(), &recording.config, recording.events.clone())
+    }
+
+    // test ended, or all events are applied and there is no time limit to run out
+    pub fn finished(&self) -> bool {
+        self.test.complete || self.next >= self.events.len() && self.test.time_limit.is_none()
+    }
+
+    // time of the next event to apply
+    pub fn next_time(&self) -> Option<Duration> {
+        self.events.get(self.next).map(|e| e.time)
+    }
+
+    // time of the first and last recorded events
+    pub fn span(&self) -> (Duration, Duration) {
+        let first = self.events.first().map(|e| e.time).unwrap_or_default();
+        let last = self.events.last().map(|e| e.time).unwrap_or_default();
+        (first, last)
+    }
+
+    // apply next event, false if nothing left
+    pub fn step(&mut self) -> bool {
+        if self.test.complete || self.next >= self.events.len() {
+            return false;
+        }
+
+        let event = &self.events[self.next];
+        self.test.handle_key_elapsed(event.key.clone(), event.time);
+        self.next += 1;
+        true
+    }
+
+    // apply all events that happened up to given time, timed test ends when time is up
+    pub fn advance_to(&mut self, time: Duration) {
+        while self.next_time().is_some_and(|t| t <= time) && self.step() {}
+        self.test.tick_elapsed(time);
+    }
+}
+
+// api function, that rebuilds raw results from recorded events
+// warns if replayed events differ from recorded ones, e.g. after changes in key handling
+pub fn replay(words: Vec<String>, config: &Config, events: &[Event]) -> ReplayResponse {
+    // recorded timed test ran until its time limit, after the last event
+    let mut replay = Replay::new(words, config, events.to_vec());
+    replay.advance_to(Duration::MAX);
+
+    let raw_results = RawResults::from(&replay.test);
+
+    if raw_results.events.len() != events.len() {
+        return Response::with_warning(
+            raw_results,
+            format!("replay stopped after {} of {} events", replay.next, events.len()),
+        );
+    }
+
+    if let Some(i) = raw_results.events.iter().zip(events).position(|(a, b)| a != b) {
+        return Response::with_warning(raw_results, format!("replay diverged at event {}", i));
+    }
+
+    Response::plain(raw_results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::validate_config,
+        results::process_results,
+        test::EndReason,
+    };
+
+    // recorded live test and its results, replay must reproduce both
+    fn check(recording: &str, results: &str) -> Replay {
+        let recording = Recording::from_json_string(recording).unwrap();
+        let validated = validate_config(recording.config.clone());
+        assert!(validated.message.is_none() && validated.payload == recording.config, "recorded config is not valid");
+
+        let response = replay(recording.words.clone(), &recording.config, &recording.events);
+        assert!(response.message.is_none(), "{:?}", response.message.map(|(_, msg)| msg));
+        assert_eq!(response.payload.events, recording.events);
+        // compared as written, parsed floats may differ in the last digit
+        let replayed = process_results(response.payload).payload;
+        assert_eq!(replayed.to_json_string().unwrap(), results.trim_end());
+
+        let mut replay = Replay::from_recording(&recording);
+        replay.advance_to(Duration::MAX);
+        replay
+    }
+
+    #[test]
+    fn words_with_correction() {
+        let replay = check(
+            include_str!("../tests/fixtures/replay/words.json"),
+            include_str!("../tests/fixtures/replay/words.results.json"),
+        );
+        assert_eq!(replay.test.end_reason, Some(EndReason::Completed));
+    }
+
+    #[test]
+    fn backtrack_to_previous_word() {
+        let replay = check(
+            include_str!("../tests/fixtures/replay/backtrack.json"),
+            include_str!("../tests/fixtures/replay/backtrack.results.json"),
+        );
+        assert_eq!(replay.test.words[0].progress, "one");
+        assert_eq!(replay.test.end_reason, Some(EndReason::Completed));
+    }
+
+    #[test]
+    fn code_with_auto_indent() {
+        let replay = check(
+            include_str!("../tests/fixtures/replay/code.json"),
+            include_str!("../tests/fixtures/replay/code.results.json"),
+        );
+        assert_eq!(replay.test.words[3].progress, "    ");
+        assert_eq!(replay.test.end_reason, Some(EndReason::Completed));
+    }
+
+    #[test]
+    fn accent_composition() {
+        let replay = check(
+            include_str!("../tests/fixtures/replay/accents.json"),
+            include_str!("../tests/fixtures/replay/accents.results.json"),
+        );
+        assert_eq!(replay.test.words[0].progress, "\u{10d}aj");
+        assert_eq!(replay.test.end_reason, Some(EndReason::Completed));
+    }
+
+    #[test]
+    fn timed_test_ends_when_time_is_up() {
+        let replay = check(
+            include_str!("../tests/fixtures/replay/timed.json"),
+            include_str!("../tests/fixtures/replay/timed.results.json"),
+        );
+        assert_eq!(replay.test.end_reason, Some(EndReason::TimeUp));
+
+        let recording = Recording::from_json_string(include_str!("../tests/fixtures/replay/timed.json")).unwrap();
+        let mut replay = Replay::from_recording(&recording);
+        replay.advance_to(Duration::from_secs(1));
+        assert!(!replay.finished());
+        replay.advance_to(Duration::from_secs(2));
+        assert!(replay.finished());
+    }
+}
@@ -0,0 +1,349 @@
+/*
+ * core/src/repo.rs
+ */
+
+use std::{
+    fs,
+    path::Path
+};
+use ignore::WalkBuilder;
+
+use crate::quotes::Quote;
+
+// larger files are generated code or data, not code to practice on
+const MAX_FILE_SIZE: u64 = 256 * 1024;
+
+// lines of code block typed as one test
+const MIN_BLOCK_LINES: usize = 3;
+const MAX_BLOCK_LINES: usize = 20;
+
+// first words of block heads, that open body without bracket at line end, e.g. lua function
+const BLOCK_KEYWORDS: &[&str] = &[
+    "fn", "impl", "struct", "enum", "trait", "mod", "def", "class", "module", "function", "func",
+    "if", "else", "elif", "for", "while", "loop", "match", "switch", "case", "do", "try",
+];
+
+// first words of import lists, they are no code to practice
+const IMPORT_KEYWORDS: &[&str] = &["use", "import", "from", "include", "require"];
+
+// comment and string syntax of source files with given extensions
+struct Syntax {
+    extensions: &'static [&'static str],
+    line_comments: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+    quotes: &'static [char],    // string delimiters, comments inside strings are kept
+    multiline: &'static [char], // delimiters of strings, that may span lines
+    chars: bool,                // single quotes make char literals, e.g. '"'
+    raw_strings: bool,          // r"..." and r#"..."# strings without escapes
+}
+
+const SYNTAXES: &[Syntax] = &[
+    Syntax {
+        extensions: &["rs"],
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        quotes: &['"'],
+        multiline: &['"'],
+        chars: true,
+        raw_strings: true,
+    },
+    Syntax {
+        extensions: &["c", "h", "cc", "cpp", "hpp", "cs", "java", "kt", "go", "swift", "scala", "dart"],
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        quotes: &['"'],
+        multiline: &[],
+        chars: true,
+        raw_strings: false,
+    },
+    Syntax {
+        extensions: &["js", "jsx", "mjs", "ts", "tsx"],
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        quotes: &['"', '\'', '`'],
+        multiline: &['`'],
+        chars: false,
+        raw_strings: false,
+    },
+    Syntax {
+        extensions: &["php"],
+        line_comments: &["//", "#"],
+        block_comment: Some(("/*", "*/")),
+        quotes: &['"', '\''],
+        multiline: &['"', '\''],
+        chars: false,
+        raw_strings: false,
+    },
+    Syntax {
+        extensions: &["py", "rb", "sh", "bash", "pl"],
+        line_comments: &["#"],
+        block_comment: None,
+        quotes: &['"', '\''],
+        multiline: &['"', '\''],
+        chars: false,
+        raw_strings: false,
+    },
+    Syntax {
+        extensions: &["lua"],
+        line_comments: &["--"],
+        block_comment: Some(("--[[", "]]")),
+        quotes: &['"', '\''],
+        multiline: &[],
+        chars: false,
+        raw_strings: false,
+    },
+];
+
+// syntax of known source file, none for other files
+fn syntax_of(path: &Path) -> Option<&'static Syntax> {
+    let extension = path.extension()?.to_str()?;
+    SYNTAXES.iter().find(|syntax| syntax.extensions.contains(&extension))
+}
+
+// api function, that collects code blocks of source files in directory as code mode snippets
+// ignored files of .gitignore, hidden, binary and oversized files are skipped
+// snippet id is file and line where block starts, e.g. 'src/main.rs:42'
+pub fn repo_snippets(dir: &str, strip_comments: bool) -> Result<Vec<Quote>, String> {
+    let root = Path::new(dir);
+    if !root.is_dir() {
+        return Err(format!("invalid source tree '{}', not a directory", dir));
+    }
+
+    // .gitignore is respected even outside of git repository, files come in stable order
+    let walker = WalkBuilder::new(root)
+        .require_git(false)
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .build();
+
+    let mut snippets = Vec::new();
+
+    // unreadable entries are skipped
+    for entry in walker.flatten() {
+        let path = entry.path();
+        let Some(syntax) = syntax_of(path) else {
+            continue;
+        };
+        if !entry.file_type().is_some_and(|kind| kind.is_file()) {
+            continue;
+        }
+        if entry.metadata().map_or(true, |meta| meta.len() > MAX_FILE_SIZE) {
+            continue;
+        }
+
+        // binary file has zero bytes or is not text at all
+        let Ok(bytes) = fs::read(path) else {
+            continue;
+        };
+        if bytes.contains(&0) {
+            continue;
+        }
+        let Ok(text) = String::from_utf8(bytes) else {
+            continue;
+        };
+
+        let name = path.strip_prefix(root).unwrap_or(path).display().to_string();
+
+        // comments are stripped from found blocks, so line numbers stay those of file
+        for (line, mut lines) in code_blocks(&text) {
+            if strip_comments {
+                lines = without_comments(&lines.join("\n"), syntax).lines().map(|line| line.to_string()).collect();
+                if lines.len() < MIN_BLOCK_LINES {
+                    continue;
+                }
+            }
+
+            let id = format!("{}:{}", name, line);
+            snippets.push(Quote { id: id.clone(), author: None, source: Some(id), lines, code: true });
+        }
+    }
+
+    Ok(snippets)
+}
+
+// blocks with their first line number, block is line with more indented lines after it,
+// closing bracket at level of its first line belongs to it too, e.g. function or loop
+fn code_blocks(text: &str) -> Vec<(usize, Vec<String>)> {
+    let lines = text.strip_prefix('\u{feff}').unwrap_or(text).lines().map(str::trim_end).collect::<Vec<_>>();
+    let indent = |line: &str| line.len() - line.trim_start().len();
+    let mut blocks = Vec::new();
+
+    for start in 0..lines.len() {
+        let head = lines[start];
+        if head.is_empty() || head.trim_start().starts_with(['}', ')', ']']) {
+            continue;
+        }
+
+        let level = indent(head);
+        let mut end = start + 1;
+        while end < lines.len() && (lines[end].is_empty() || indent(lines[end]) > level) {
+            end += 1;
+        }
+        if end < lines.len() && indent(lines[end]) == level && (lines[end].trim_start().starts_with(['}', ')', ']']) || lines[end].trim() == "end") {
+            end += 1;
+        }
+        while end > start && lines[end - 1].is_empty() {
+            end -= 1;
+        }
+
+        // block has body, line after head is more indented
+        let has_body = lines.get(start + 1).is_some_and(|line| !line.is_empty() && indent(line) > level);
+        if !has_body || !opens_body(head) || !(MIN_BLOCK_LINES..=MAX_BLOCK_LINES).contains(&(end - start)) {
+            continue;
+        }
+
+        // block starts without indentation
+        let block = lines[start..end]
+            .iter()
+            .map(|line| line.get(level..).unwrap_or(line.trim_start()).to_string())
+            .collect();
+        blocks.push((start + 1, block));
+    }
+
+    blocks
+}
+
+// head of function or other block, e.g. 'fn main() {' or 'def main():', not of import list or call arguments
+fn opens_body(head: &str) -> bool {
+    let words = head
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|word| !word.is_empty())
+        .skip_while(|word| ["pub", "crate", "export", "local", "async", "static"].contains(word))
+        .take(2)
+        .collect::<Vec<_>>();
+
+    // nested group of rust import list, e.g. 'config::{', is part of import too
+    if words.first().is_some_and(|word| IMPORT_KEYWORDS.contains(word)) || head.ends_with("::{") {
+        return false;
+    }
+    head.ends_with(['{', ':']) || words.iter().any(|word| BLOCK_KEYWORDS.contains(word))
+}
+
+// length of char literal at start of text, e.g. '"' or '\'', none for rust lifetime like 'a
+fn char_literal(text: &str) -> Option<usize> {
+    let mut chars = text.char_indices().skip(1);
+    let (_, c) = chars.next().filter(|(_, c)| *c != '\n')?;
+
+    // escape is at most '\u{10ffff}'
+    let limit = if c == '\\' {
+        chars.next()?;
+        9
+    } else {
+        1
+    };
+    chars.take(limit).find(|(_, c)| *c == '\'').map(|(i, _)| i + 1)
+}
+
+// length of raw string at start of text, e.g. r#"say "hi""#, with byte string prefix too
+fn raw_string(text: &str) -> Option<usize> {
+    let raw = text.strip_prefix('b').unwrap_or(text).strip_prefix('r')?;
+    let hashes = raw.len() - raw.trim_start_matches('#').len();
+    let body = raw[hashes..].strip_prefix('"')?;
+
+    let close = format!("\"{}", "#".repeat(hashes));
+    let open = text.len() - body.len();
+    Some(body.find(&close).map_or(text.len(), |i| open + i + close.len()))
+}
+
+// source text without comments, lines with only comment are removed
+fn without_comments(text: &str, syntax: &Syntax) -> String {
+    let mut result = String::new();
+    let mut rest = text;
+    let mut string: Option<char> = None;
+
+    while let Some(c) = rest.chars().next() {
+        // string literal goes as is, including escaped delimiters
+        if let Some(delimiter) = string {
+            let len = if c == '\\' { rest.chars().take(2).map(char::len_utf8).sum() } else { c.len_utf8() };
+            if c == delimiter || c == '\n' && !syntax.multiline.contains(&delimiter) {
+                string = None;
+            }
+            result.push_str(&rest[..len]);
+            rest = &rest[len..];
+            continue;
+        }
+
+        let comment_end = if let Some((open, close)) = syntax.block_comment.filter(|(open, _)| rest.starts_with(open)) {
+            rest[open.len()..].find(close).map_or(rest.len(), |i| open.len() + i + close.len())
+        } else if syntax.line_comments.iter().any(|start| rest.starts_with(start)) {
+            rest.find('\n').unwrap_or(rest.len())
+        } else {
+            0
+        };
+
+        if comment_end > 0 {
+            rest = &rest[comment_end..];
+
+            // line left without code is dropped with its break
+            let line_start = result.rfind('\n').map_or(0, |i| i + 1);
+            if result[line_start..].trim().is_empty() && (rest.is_empty() || rest.starts_with('\n')) {
+                result.truncate(line_start);
+                rest = rest.strip_prefix('\n').unwrap_or(rest);
+            }
+            continue;
+        }
+
+        // char literal and raw string go as is, raw string starts a word, e.g. not 'for'
+        let literal = match c {
+            '\'' if syntax.chars => char_literal(rest),
+            'b' | 'r' if syntax.raw_strings && !result.ends_with(|c: char| c.is_alphanumeric() || c == '_') => raw_string(rest),
+            _ => None,
+        };
+        if let Some(len) = literal {
+            result.push_str(&rest[..len]);
+            rest = &rest[len..];
+            continue;
+        }
+
+        if syntax.quotes.contains(&c) {
+            string = Some(c);
+        }
+        result.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strip(text: &str, file: &str) -> String {
+        without_comments(text, syntax_of(Path::new(file)).unwrap())
+    }
+
+    #[test]
+    fn quote_in_char_literal_is_no_string() {
+        assert_eq!(strip("let q = '\"'; // quote\nlet e = '\\''; // apostrophe", "main.rs"), "let q = '\"'; \nlet e = '\\''; ");
+        assert_eq!(strip("if (c == '\"') { // quote\n}", "main.c"), "if (c == '\"') { \n}");
+        assert_eq!(strip("fn f<'a>(s: &'a str) {} // lifetime", "main.rs"), "fn f<'a>(s: &'a str) {} ");
+    }
+
+    #[test]
+    fn comment_in_multiline_string_is_kept() {
+        let raw = "let help = r#\"\n// usage: \"typecrab\" # kept\n\"#;";
+        assert_eq!(strip(raw, "main.rs"), raw);
+
+        let text = "let help = \"first\n// kept\";";
+        assert_eq!(strip(text, "main.rs"), text);
+        assert_eq!(strip("let q = r\"\\\"; // raw", "main.rs"), "let q = r\"\\\"; ");
+    }
+
+    #[test]
+    fn import_list_is_no_block() {
+        let text = "use crate::{\n    config::{\n        Config,\n        GameMode\n    },\n    quotes::Quote,\n};\n\nfn main() {\n    let a = 1;\n    let b = 2;\n}\n";
+        let blocks = code_blocks(text);
+
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].0, 9);
+        assert_eq!(blocks[0].1[0], "fn main() {");
+    }
+
+    #[test]
+    fn call_arguments_are_no_block() {
+        let text = "def main():\n    run(\n        fast,\n        quiet,\n    )\n";
+        let blocks = code_blocks(text);
+
+        assert_eq!(blocks.iter().map(|(line, _)| *line).collect::<Vec<_>>(), vec![1]);
+    }
+}
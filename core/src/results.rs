@@ -5,14 +5,26 @@
 use std::time::Duration;
 use std::collections::HashMap;
 
-use crate::{response::Response};
+use serde::{Serialize, Deserialize};
+
+use crate::{
+    response::Response,
+    schema::{
+        duration_ms,
+        from_versioned_json,
+        migrate_final_results,
+        migrate_raw_results,
+        to_versioned_json
+    }
+};
 
 // key representation
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Key {
     Char(char),
     Enter,
     Space,
+    Tab,
     Backspace,
     Escape,
     CtrlC,
@@ -20,21 +32,31 @@ pub enum Key {
 }
 
 // all events representation
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Event {
+    #[serde(with = "duration_ms")]
     pub time: Duration,         // when it happened
     pub key: Key,               // what key
     pub correct: Option<bool>,  // true - correct, false - mistake, none - system move
+    #[serde(default)]
+    pub accent: bool,           // typed letter differs only in accent, correct in lenient mode
 }
 
 // one word representation
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Word {
     pub text: String,           // what needed to enter
     pub progress: String,       // what already entered
     pub events: Vec<Event>,     // all events
 }
 
+impl Word {
+    // leading whitespace of code line, it is typed without separator before first token
+    pub fn is_indent(&self) -> bool {
+        !self.text.is_empty() && self.text.chars().all(|c| c == ' ' || c == '\t')
+    }
+}
+
 impl From<String> for Word {
     fn from(string: String) -> Self {
         Word {
@@ -46,12 +68,23 @@ impl From<String> for Word {
 }
 
 // raw test results representation
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RawResults {
     pub words: Vec<Word>,
     pub events: Vec<Event>,
 }
-#[derive(Debug, Clone, PartialEq)] 
+
+impl RawResults {
+    pub fn to_json_string(&self) -> Result<String, serde_json::Error> {
+        to_versioned_json(self)
+    }
+
+    pub fn from_json_string(json: &str) -> Result<Self, serde_json::Error> {
+        from_versioned_json(json, migrate_raw_results)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct KeyPresses {
     pub correct: usize,    
     pub incorrect: usize,
@@ -71,23 +104,46 @@ impl Default for KeyPresses {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)] 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FinalResults {
     pub wpm: f64,                       // Words per minute (correct chars only)
     pub raw_wpm: f64,                   // Words per minute (all chars)
     pub key_presses: KeyPresses,        // keypresses data
     pub accuracy: f64,                  // Percentage of correct keypresses  
+    pub lenient_accuracy: f64,          // Percentage of correct keypresses, accents ignored
     pub consistency: f64,               // Consistency score 
-    pub graph_data: Vec<(f64, f64, f64, usize, usize, usize)>, // (time, wpm, raw_wpm, incorrect, extra, missed)
+    pub graph_data: Vec<GraphPoint>,    // per second progress
     pub errors: Vec<(char, usize)>
 }
 
+impl FinalResults {
+    pub fn to_json_string(&self) -> Result<String, serde_json::Error> {
+        to_versioned_json(self)
+    }
+
+    pub fn from_json_string(json: &str) -> Result<Self, serde_json::Error> {
+        from_versioned_json(json, migrate_final_results)
+    }
+}
+
+// one point of results graph, cumulative since test start
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GraphPoint {
+    pub time: f64,          // seconds since first keypress
+    pub wpm: f64,
+    pub raw_wpm: f64,
+    pub incorrect: usize,
+    pub extra: usize,
+    pub missed: usize,
+}
+
 impl Default for FinalResults {
     fn default() -> Self {
         FinalResults {
             wpm: 0.0,
             raw_wpm: 0.0,
             accuracy: 0.0,
+            lenient_accuracy: 0.0,
             consistency: 0.0,
             key_presses: KeyPresses::default(),
             graph_data: Vec::new(),
@@ -104,6 +160,8 @@ pub fn process_results(raw_results: RawResults) -> Response<FinalResults> {
     let mut correct_keypresses = 0;
     let mut incorrect_keypresses = 0;
     let mut extra_keypress = 0;
+    let mut accent_correct = 0;
+    let mut accent_incorrect = 0;
     let mut missed_keypresses = 0;
     let mut key_errors: HashMap<(char, char), usize> = HashMap::new();
     let mut graph_data = Vec::new();
@@ -133,6 +191,13 @@ pub fn process_results(raw_results: RawResults) -> Response<FinalResults> {
 
         for event in word.events.iter() {
             if let Some(correct) = event.correct {
+                // accent mistakes are correct only in lenient mode
+                if event.accent && correct {
+                    accent_correct += 1;
+                } else if event.accent {
+                    accent_incorrect += 1;
+                }
+
                 if char_index >= expected.len() {
                     // Handle case where char_index exceeds expected length
                 }
@@ -184,10 +249,14 @@ pub fn process_results(raw_results: RawResults) -> Response<FinalResults> {
 
     let total_keypresses = correct_keypresses + incorrect_keypresses + extra_keypress;
 
-    let accuracy = if total_keypresses > 0 {
-        (correct_keypresses as f64 / total_keypresses as f64) * 100.0
+    // strict accuracy counts accent mistakes, lenient one does not, whichever mode test ran in
+    let (accuracy, lenient_accuracy) = if total_keypresses > 0 {
+        (
+            ((correct_keypresses - accent_correct) as f64 / total_keypresses as f64) * 100.0,
+            ((correct_keypresses + accent_incorrect) as f64 / total_keypresses as f64) * 100.0,
+        )
     } else {
-        0.0
+        (0.0, 0.0)
     };
 
     // Graph data calculation
@@ -230,14 +299,14 @@ pub fn process_results(raw_results: RawResults) -> Response<FinalResults> {
                 0.0
             };
 
-            graph_data.push((
-                event_time,
-                current_wpm,
-                current_raw_wpm,
-                current_incorrect,
-                current_extra,
-                current_missed
-            ));
+            graph_data.push(GraphPoint {
+                time: event_time,
+                wpm: current_wpm,
+                raw_wpm: current_raw_wpm,
+                incorrect: current_incorrect,
+                extra: current_extra,
+                missed: current_missed
+            });
             last_time_secs = event_time.floor();
         }
     }
@@ -283,6 +352,7 @@ pub fn process_results(raw_results: RawResults) -> Response<FinalResults> {
         wpm,
         raw_wpm,
         accuracy,
+        lenient_accuracy,
         consistency,
         key_presses: KeyPresses {
             correct: correct_keypresses,
@@ -294,3 +364,53 @@ pub fn process_results(raw_results: RawResults) -> Response<FinalResults> {
         errors
     })
 }
+
+// word is slow if its time per character exceeds median one by this factor
+const SLOW_WORD_FACTOR: f64 = 1.5;
+
+// api function, that returns mistyped and slow words of finished test, in order of appearance without duplicates
+pub fn missed_words(raw_results: &RawResults) -> Vec<String> {
+    let paces = raw_results.words.iter().map(word_pace).collect::<Vec<_>>();
+
+    let mut sorted = paces.iter().flatten().copied().collect::<Vec<_>>();
+    sorted.sort_by(f64::total_cmp);
+    let median = sorted.get(sorted.len() / 2).copied();
+
+    // last reached word may be cut by time limit, unfinished part is not a mistake
+    let last_reached = raw_results.words.iter().rposition(|w| !w.events.is_empty());
+
+    let mut words: Vec<String> = Vec::new();
+
+    for (i, (word, pace)) in raw_results.words.iter().zip(&paces).enumerate() {
+        if word.events.is_empty() {
+            continue;
+        }
+
+        let incorrect = word.events.iter().any(|e| e.correct == Some(false));
+        let unfinished = word.progress != word.text && Some(i) != last_reached;
+        let slow = matches!((pace, median), (Some(p), Some(m)) if *p > m * SLOW_WORD_FACTOR);
+
+        if incorrect || unfinished || slow {
+            let text = word.text.trim().to_string();
+            if !text.is_empty() && !words.contains(&text) {
+                words.push(text);
+            }
+        }
+    }
+
+    words
+}
+
+// seconds per character between first and last keystroke of word, none if it can not be measured
+fn word_pace(word: &Word) -> Option<f64> {
+    let times = word.events.iter().filter(|e| e.correct.is_some()).map(|e| e.time);
+    let first = times.clone().min()?;
+    let last = times.max()?;
+    let chars = word.text.trim_end_matches('\n').chars().count();
+
+    if chars < 2 || last <= first {
+        return None;
+    }
+
+    Some((last - first).as_secs_f64() / (chars - 1) as f64)
+}
@@ -0,0 +1,137 @@
+/*
+ * core/src/schema.rs
+ */
+
+use serde::{
+    de::{DeserializeOwned, Error},
+    Serialize,
+};
+use serde_json::Value;
+
+// version of serialized results format, bump on breaking changes and add migration step
+pub const SCHEMA_VERSION: u32 = 2;
+
+// versioned wrapper, version field is written next to data fields
+#[derive(Serialize)]
+struct Versioned<'a, T> {
+    version: u32,
+    #[serde(flatten)]
+    data: &'a T,
+}
+
+// migration hook, upgrades json value of given version to the current one
+pub type Migration = fn(Value, u32) -> Result<Value, String>;
+
+pub(crate) fn to_versioned_json<T: Serialize>(data: &T) -> Result<String, serde_json::Error> {
+    serde_json::to_string(&Versioned { version: SCHEMA_VERSION, data })
+}
+
+pub(crate) fn from_versioned_json<T: DeserializeOwned>(json: &str, migrate: Migration) -> Result<T, serde_json::Error> {
+    let value: Value = serde_json::from_str(json)?;
+    let version = version_of(&value);
+
+    let value = migrate(value, version).map_err(serde_json::Error::custom)?;
+    serde_json::from_value(value)
+}
+
+// data without version field is considered version 0
+pub(crate) fn version_of(value: &Value) -> u32 {
+    value.get("version").and_then(Value::as_u64).unwrap_or(0) as u32
+}
+
+// common check for every migration
+pub(crate) fn check_version(version: u32) -> Result<(), String> {
+    if version > SCHEMA_VERSION {
+        Err(format!("unsupported schema version {}, newest known is {}", version, SCHEMA_VERSION))
+    } else {
+        Ok(())
+    }
+}
+
+// raw results were not serializable before version 1
+pub fn migrate_raw_results(value: Value, version: u32) -> Result<Value, String> {
+    check_version(version)?;
+    Ok(value)
+}
+
+// recordings were not serializable before version 1
+pub fn migrate_recording(value: Value, version: u32) -> Result<Value, String> {
+    check_version(version)?;
+    Ok(value)
+}
+
+pub fn migrate_final_results(mut value: Value, version: u32) -> Result<Value, String> {
+    check_version(version)?;
+
+    // 0 -> 1: graph data points from (time, wpm, raw_wpm, incorrect, extra, missed) tuples to named fields
+    if version < 1 {
+        if let Some(Value::Array(points)) = value.get_mut("graph_data") {
+            for point in points.iter_mut() {
+                if let Value::Array(fields) = point {
+                    if fields.len() != 6 {
+                        return Err("invalid graph data point".to_string());
+                    }
+                    let mut named = serde_json::Map::new();
+                    for (name, field) in ["time", "wpm", "raw_wpm", "incorrect", "extra", "missed"].iter().zip(fields.drain(..)) {
+                        named.insert(name.to_string(), field);
+                    }
+                    *point = Value::Object(named);
+                }
+            }
+        }
+    }
+
+    // 1 -> 2: accents were always strict, so lenient accuracy is the same as accuracy
+    if version < 2 {
+        if let Some(accuracy) = value.get("accuracy").cloned() {
+            value["lenient_accuracy"] = accuracy;
+        }
+    }
+
+    Ok(value)
+}
+
+// durations are stored as whole milliseconds
+pub(crate) mod duration_ms {
+    use std::time::Duration;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(duration.as_millis() as u64)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        u64::deserialize(deserializer).map(Duration::from_millis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use super::SCHEMA_VERSION;
+    use crate::results::{
+        FinalResults,
+        GraphPoint
+    };
+
+    #[test]
+    fn results_of_first_format_are_migrated() {
+        let point = GraphPoint { time: 1.0, wpm: 42.0, raw_wpm: 48.0, incorrect: 1, extra: 0, missed: 2 };
+        let current = FinalResults { wpm: 42.0, accuracy: 96.5, graph_data: vec![point], ..FinalResults::default() };
+
+        // written before versions, graph points were tuples and there was no lenient accuracy
+        let mut old = serde_json::to_value(&current).unwrap();
+        old["graph_data"] = json!([[1.0, 42.0, 48.0, 1, 0, 2]]);
+        old.as_object_mut().unwrap().remove("lenient_accuracy");
+
+        let migrated = FinalResults::from_json_string(&old.to_string()).unwrap();
+        assert_eq!(migrated, FinalResults { lenient_accuracy: 96.5, ..current });
+    }
+
+    #[test]
+    fn newer_version_is_rejected() {
+        let version = |version: u32| format!("\"version\":{}", version);
+        let json = FinalResults::default().to_json_string().unwrap().replace(&version(SCHEMA_VERSION), &version(SCHEMA_VERSION + 1));
+        assert!(FinalResults::from_json_string(&json).is_err());
+    }
+}
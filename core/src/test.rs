@@ -2,50 +2,160 @@
  * core/test.rs
  */
 
-use instant::Instant; 
+use std::time::Duration;
+use serde::{Serialize, Deserialize};
 
 use crate::{
     Config,
+    clock::{
+        Clock,
+        SystemClock
+    },
     results::{
         Key,
         Event,
         Word,
         RawResults
     },
-    GameMode
+    GameMode,
+    matching::{
+        normalize,
+        is_accent,
+        match_prefix,
+        lenient_prefix,
+        Match
+    }
 };
 
+// spaces typed by tab in code mode
+const TAB_WIDTH: usize = 4;
+
+// why test ended
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum EndReason {
+    Completed,
+    TimeUp,
+    Died,
+    Aborted,
+}
+
+impl EndReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EndReason::Completed => "completed",
+            EndReason::TimeUp => "time up",
+            EndReason::Died => "died",
+            EndReason::Aborted => "aborted",
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct Test {
     pub words: Vec<Word>,
     pub current_word: usize,
     pub complete: bool,
+    pub end_reason: Option<EndReason>,
     pub backtrack: bool,
     pub death: bool,
+    pub lenient_accents: bool,
     pub mode: GameMode,
-    start_time: Instant,
+    pub(crate) log: Vec<Event>, // all events in order they happened
+    pub(crate) time_limit: Option<Duration>,
+    clock: Box<dyn Clock>,
+    start: Option<Duration>,    // clock time of first keystroke
 }
 
 impl Test {
     pub fn new(words: Vec<String>, config: &Config) -> Self {
-        let words = words.into_iter().map(Word::from).collect();
+        Self::with_clock(words, config, Box::new(SystemClock))
+    }
+
+    pub fn with_clock(words: Vec<String>, config: &Config, clock: Box<dyn Clock>) -> Self {
+        let words = words.into_iter().map(|word| Word::from(normalize(&word))).collect();
         Self {
             words,
             current_word: 0,
             complete: false,
+            end_reason: None,
             backtrack: config.backtrack,
             death: config.death,
+            lenient_accents: config.lenient_accents,
             mode: config.mode,
-            start_time: Instant::now(),
+            log: Vec::new(),
+            time_limit: config.time_limit.map(|limit| Duration::from_secs(limit as u64)),
+            clock,
+            start: None,
+        }
+    }
+
+    // timing starts with the first keystroke
+    pub fn started(&self) -> bool {
+        self.start.is_some()
+    }
+
+    // time since first keystroke by test clock
+    pub fn elapsed(&self) -> Duration {
+        match self.start {
+            Some(start) => self.clock.now().saturating_sub(start),
+            None => Duration::ZERO,
+        }
+    }
+
+    // time left by test clock, none if test is not timed
+    pub fn remaining(&self) -> Option<Duration> {
+        self.time_limit.map(|limit| limit.saturating_sub(self.elapsed()))
+    }
+
+    // end test if time limit is reached at given timestamp
+    pub fn tick(&mut self, now: Duration) {
+        if let Some(start) = self.start {
+            self.tick_elapsed(now.saturating_sub(start));
+        }
+    }
+
+    // end test if time limit is reached at given time since test start, e.g. in replay
+    pub(crate) fn tick_elapsed(&mut self, elapsed: Duration) {
+        if !self.complete && self.time_limit.is_some_and(|limit| elapsed >= limit) {
+            self.finish(EndReason::TimeUp);
         }
     }
 
     pub fn handle_key(&mut self, key: Key) {
-        let elapsed = self.start_time.elapsed();
+        let now = self.clock.now();
+        self.handle_key_at(key, now);
+    }
+
+    // process key pressed at given timestamp, must be in the same time base as test clock
+    pub fn handle_key_at(&mut self, key: Key, timestamp: Duration) {
+        // keys after time is up are not counted
+        self.tick(timestamp);
+        if self.complete {
+            return;
+        }
 
+        // modifiers, backspace and escape alone do not start the test, tab types only in code mode
+        let typing = match key {
+            Key::Other(_) | Key::Backspace | Key::Escape | Key::CtrlC => false,
+            Key::Tab => self.mode == GameMode::Code,
+            _ => true,
+        };
+        if self.start.is_none() && !typing {
+            // test can still be left before it starts
+            if matches!(key, Key::Escape | Key::CtrlC) {
+                self.handle_key_elapsed(key, Duration::ZERO);
+            }
+            return;
+        }
+
+        let start = *self.start.get_or_insert(timestamp);
+        self.handle_key_elapsed(key, timestamp.saturating_sub(start));
+    }
+
+    // process key as pressed at given time since test start
+    pub(crate) fn handle_key_elapsed(&mut self, key: Key, elapsed: Duration) {
         if self.words.is_empty() {
-            self.complete = true;
+            self.finish(EndReason::Completed);
             return;
         }
 
@@ -55,8 +165,8 @@ impl Test {
         if self.mode == GameMode::Zen {
             match key {
                 Key::CtrlC | Key::Escape => {
-                    current.events.push(Event { time: elapsed, key, correct: None });
-                    self.complete = true;
+                    self.record(Event { time: elapsed, key, correct: None, accent: false });
+                    self.finish(EndReason::Completed);
                 }
 
                 Key::Enter | Key::Space => {
@@ -64,7 +174,7 @@ impl Test {
                         return;
                     }
 
-                    current.events.push(Event { time: elapsed, key, correct: None });
+                    self.record(Event { time: elapsed, key, correct: None, accent: false });
                     self.next_word();
                 }
 
@@ -74,23 +184,20 @@ impl Test {
                         if self.backtrack && self.current_word > 0 {
                             self.prev_word();
 
-                            self.words[self.current_word].events.push(Event {
-                                time: elapsed,
-                                key,
-                                correct: None,
-                            });
+                            self.record(Event { time: elapsed, key, correct: None, accent: false });
                         }
                     } else {
                         current.progress.pop();
                         current.text.pop();
-                        current.events.push(Event { time: elapsed, key, correct: None });
+                        self.record(Event { time: elapsed, key, correct: None, accent: false });
                     }
                 }
 
+                // combining accent is composed with letter before it
                 Key::Char(c) => {
-                    current.progress.push(c);
-                    current.text.push(c);
-                    current.events.push(Event { time: elapsed, key, correct: Some(true) });
+                    current.progress = normalize(&format!("{}{}", current.progress, c));
+                    current.text = current.progress.clone();
+                    self.record(Event { time: elapsed, key, correct: Some(true), accent: false });
                 }
 
                 _ => {}
@@ -99,31 +206,33 @@ impl Test {
             return;
         }
 
-        // other modes
+        // other modes, code mode types its whitespace and line breaks too
         match key {
             // end current test
             Key::CtrlC | Key::Escape => {
-                current.events.push(Event {
-                    time: elapsed,
-                    key,
-                    correct: None,
-                });
-                self.complete = true;
+                self.record(Event { time: elapsed, key, correct: None, accent: false });
+                self.finish(EndReason::Aborted);
+            }
+
+            // line break is typed, next line is indented automatically
+            Key::Enter if self.mode == GameMode::Code => self.type_text("\n", key, elapsed),
+
+            // indentation is typed with spaces or tab
+            Key::Space if self.mode == GameMode::Code && current.is_indent() => self.type_text(" ", key, elapsed),
+            Key::Tab if self.mode == GameMode::Code => {
+                let indent = expected_indent(current);
+                self.type_text(&indent, key, elapsed);
             }
 
             // finalize current word
             Key::Enter | Key::Space => {
                 if !current.progress.is_empty() || current.text.is_empty() {
                     let correct = current.text == current.progress;
-                    current.events.push(Event {
-                        time: elapsed,
-                        key,
-                        correct: None,
-                    });
+                    self.record(Event { time: elapsed, key, correct: None, accent: false });
 
                     // end test if wrong and sudden death enabled
                     if self.death && !correct {
-                        self.complete = true;
+                        self.finish(EndReason::Died);
                         return;
                     } else {
                         self.next_word();
@@ -137,47 +246,123 @@ impl Test {
                     if self.backtrack && self.current_word > 0 {
                         self.prev_word();
                         // save backspace key press in prev word
-                        self.words[self.current_word].events.push(Event {
-                            time: elapsed,
-                            key,
-                            correct: None,
-                        });
+                        self.record(Event { time: elapsed, key, correct: None, accent: false });
                     }
                 } else {
                     current.progress.pop();
-                    current.events.push(Event {
-                        time: elapsed,
-                        key,
-                        correct: None,
-                    });
+                    self.record(Event { time: elapsed, key, correct: None, accent: false });
                 }
             }
 
             // process character input
-            Key::Char(c) => {
-                current.progress.push(c);
-
-                let partial_correct = current.text.starts_with(&current.progress);
-                current.events.push(Event {
-                    time: elapsed,
-                    key: key.clone(),
-                    correct: Some(partial_correct),
-                });
-
-                if self.death && !partial_correct {
-                    self.complete = true;
-                    return;
-                }
-
-                if current.progress == current.text && self.current_word == self.words.len() - 1 {
-                    self.complete = true;
-                }
-            }
+            Key::Char(c) => self.type_text(&c.to_string(), key, elapsed),
 
             _ => {}
         }
     }
 
+    // push typed text to current word, e.g. character, or line break and indentation in code mode
+    fn type_text(&mut self, typed: &str, key: Key, elapsed: Duration) {
+        // code line continues right after its indentation, without separator
+        let current = &self.words[self.current_word];
+        if self.mode == GameMode::Code && current.is_indent() && current.progress == current.text && !typed.starts_with([' ', '\t']) {
+            self.next_word();
+        }
+
+        // combining accent is composed with letter typed right before it, not with one taken from text
+        let current = &mut self.words[self.current_word];
+        let composed = match current.events.last() {
+            Some(Event { key: Key::Char(c), correct: Some(_), .. }) if is_accent(typed) => Some(*c),
+            _ => None,
+        };
+
+        let mut progress = current.progress.clone();
+        if let Some(c) = composed {
+            progress.pop();
+            progress.push(c);
+        }
+
+        // in lenient mode letter is taken with accent of text
+        let progress = normalize(&format!("{}{}", progress, typed));
+        let matched = match_prefix(&current.text, &progress);
+
+        current.progress = match (matched, self.lenient_accents) {
+            (Match::Accents, true) => lenient_prefix(&current.text, &progress).map_or(progress, str::to_string),
+            _ => progress,
+        };
+
+        let partial_correct = matched == Match::Exact || matched == Match::Accents && self.lenient_accents;
+        let accent = matched == Match::Accents;
+        let finished = current.progress == current.text;
+
+        // line with mistakes is left too, like word with space
+        let line_end = key == Key::Enter && {
+            let breaks = &current.text[current.text.trim_end_matches('\n').len()..];
+            !breaks.is_empty() && current.progress.ends_with(breaks)
+        };
+
+        let amend = composed.is_some() && matched == Match::Exact;
+
+        // letter is right once its accent is typed
+        if amend {
+            self.amend_last();
+        }
+        self.record(Event { time: elapsed, key, correct: Some(partial_correct), accent });
+
+        if self.death && !partial_correct {
+            self.finish(EndReason::Died);
+            return;
+        }
+
+        if finished && self.current_word == self.words.len() - 1 {
+            self.finish(EndReason::Completed);
+        } else if line_end {
+            self.next_word();
+            self.auto_indent();
+        }
+    }
+
+    // indentation of new line is filled in as in editor, it is not counted as typed
+    fn auto_indent(&mut self) {
+        let last = self.words.len() - 1;
+        let current = &mut self.words[self.current_word];
+
+        if current.is_indent() && current.progress.is_empty() && self.current_word < last {
+            current.progress = current.text.clone();
+            self.current_word += 1;
+        }
+    }
+
+    // append words to the end of running test, e.g. from words stream
+    pub fn extend_words(&mut self, words: Vec<String>) {
+        if self.complete || self.mode == GameMode::Zen {
+            return;
+        }
+
+        self.words.extend(words.into_iter().map(|word| Word::from(normalize(&word))));
+    }
+
+    // mark test complete, first reason wins
+    fn finish(&mut self, reason: EndReason) {
+        self.complete = true;
+        self.end_reason.get_or_insert(reason);
+    }
+
+    // save event in current word and in test log
+    fn record(&mut self, event: Event) {
+        self.log.push(event.clone());
+        self.words[self.current_word].events.push(event);
+    }
+
+    // mark last event correct, it is the last one of current word too
+    fn amend_last(&mut self) {
+        let word = &mut self.words[self.current_word];
+        for event in [self.log.last_mut(), word.events.last_mut()].into_iter().flatten() {
+            event.correct = Some(true);
+            event.accent = false;
+        }
+    }
+
     fn prev_word(&mut self) {
         if self.current_word > 0 {
             self.current_word -= 1;
@@ -194,7 +379,7 @@ impl Test {
         } else {
             // mark test complete if last word reached
             if self.current_word == self.words.len() - 1 {
-                self.complete = true;
+                self.finish(EndReason::Completed);
             } else {
                 self.current_word += 1;
             }
@@ -202,11 +387,147 @@ impl Test {
     }
 }
 
+// whitespace expected at caret, one tab or up to tab width of spaces, tab if none is expected
+fn expected_indent(word: &Word) -> String {
+    let rest = word.text.strip_prefix(word.progress.as_str()).unwrap_or("");
+
+    if rest.starts_with('\t') {
+        return "\t".to_string();
+    }
+
+    let spaces = rest.chars().take(TAB_WIDTH).take_while(|&c| c == ' ').count();
+    if spaces == 0 {
+        "\t".to_string()
+    } else {
+        " ".repeat(spaces)
+    }
+}
+
 impl From<&Test> for RawResults {
     fn from(test: &Test) -> Self {
         let words = test.words.clone();
-        let events = words.iter().flat_map(|w| w.events.clone()).collect();
+        let events = test.log.clone();
 
         RawResults { words, events }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+
+    const SECOND: Duration = Duration::from_secs(1);
+
+    // timed test of given words, driven by manual clock
+    fn timed_test(words: &str, limit: u32) -> (Test, ManualClock) {
+        let clock = ManualClock::new();
+        let config = Config { time_limit: Some(limit), ..Config::default() };
+        let words = words.split(' ').map(String::from).collect();
+        (Test::with_clock(words, &config, Box::new(clock.clone())), clock)
+    }
+
+    #[test]
+    fn accents_are_lenient_and_input_is_composed() {
+        let config = crate::config::validate_config(Config { lenient_accents: true, ..Config::default() }).payload;
+        let words = ["café", "über"].map(String::from).to_vec();
+        let mut test = Test::with_clock(words, &config, Box::new(ManualClock::new()));
+
+        // 'ü' of dead key comes as 'u' and combining diaeresis
+        for c in "cafe u\u{308}ber".chars() {
+            test.handle_key(if c == ' ' { Key::Space } else { Key::Char(c) });
+        }
+
+        assert!(test.complete);
+        assert_eq!(test.words[0].progress, "café");
+        assert_eq!(test.words[1].progress, "über");
+    }
+
+    #[test]
+    fn time_is_up_at_limit() {
+        let (mut test, clock) = timed_test("one two", 30);
+        clock.set(5 * SECOND);
+        test.handle_key(Key::Char('o'));
+
+        test.tick(clock.now() + 30 * SECOND - Duration::from_millis(1));
+        assert!(!test.complete);
+
+        test.tick(clock.now() + 30 * SECOND);
+        assert!(test.complete);
+        assert_eq!(test.end_reason, Some(EndReason::TimeUp));
+    }
+
+    #[test]
+    fn key_after_limit_is_ignored() {
+        let (mut test, clock) = timed_test("one two", 10);
+        test.handle_key(Key::Char('o'));
+
+        clock.advance(10 * SECOND);
+        test.handle_key(Key::Char('n'));
+
+        assert_eq!(test.end_reason, Some(EndReason::TimeUp));
+        assert_eq!(test.words[0].progress, "o");
+        assert_eq!(test.log.len(), 1);
+    }
+
+    #[test]
+    fn remaining_time_counts_from_first_key() {
+        let (mut test, clock) = timed_test("one two", 10);
+        clock.advance(3 * SECOND);
+        assert_eq!(test.remaining(), Some(10 * SECOND));
+
+        test.handle_key(Key::Char('o'));
+        clock.advance(4 * SECOND);
+        assert_eq!(test.remaining(), Some(6 * SECOND));
+
+        clock.advance(20 * SECOND);
+        assert_eq!(test.remaining(), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn end_reason_tells_why_test_ended() {
+        let typed = |config: &Config, keys: &str| {
+            let mut test = Test::with_clock(vec!["ab".to_string(), "cd".to_string()], config, Box::new(ManualClock::new()));
+            for c in keys.chars() {
+                test.handle_key(if c == ' ' { Key::Space } else { Key::Char(c) });
+            }
+            test.end_reason
+        };
+
+        assert_eq!(typed(&Config::default(), "ab cd"), Some(EndReason::Completed));
+        assert_eq!(typed(&Config { death: true, ..Config::default() }, "ax "), Some(EndReason::Died));
+        assert_eq!(typed(&Config::default(), "ax "), None);
+    }
+
+    #[test]
+    fn backspace_does_not_start_timing() {
+        let (mut test, clock) = timed_test("one two", 10);
+        test.handle_key(Key::Backspace);
+        assert!(!test.started());
+
+        clock.advance(20 * SECOND);
+        test.tick(clock.now());
+        assert!(!test.complete);
+    }
+
+    #[test]
+    fn escape_leaves_test_without_starting_it() {
+        let (mut test, _) = timed_test("one two", 10);
+        test.handle_key(Key::Escape);
+
+        assert!(!test.started());
+        assert_eq!(test.end_reason, Some(EndReason::Aborted));
+    }
+
+    #[test]
+    fn key_is_timed_when_it_was_pressed() {
+        // browser handles events later than they happen
+        let (mut test, clock) = timed_test("one two", 10);
+        clock.set(9 * SECOND);
+        test.handle_key_at(Key::Char('o'), 2 * SECOND);
+        test.handle_key_at(Key::Char('n'), 3 * SECOND);
+
+        assert_eq!(test.log[1].time, SECOND);
+        assert_eq!(test.remaining(), Some(3 * SECOND));
+    }
+}
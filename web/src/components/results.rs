@@ -1,6 +1,10 @@
 use dioxus::prelude::*;
 use std::collections::HashSet;
-use typingcore::results::FinalResults;
+use typingcore::{
+    results::FinalResults,
+    EndReason,
+    Quote
+};
 
 fn x_axis_labels(x_max: f64) -> Vec<Element> {
     let step = if x_max >= 15.0 { 5 } else { 1 };
@@ -88,6 +92,9 @@ fn error_point_circles(err_pts: &Vec<(f64, f64)>, x_max: f64, y_top: u64) -> Vec
 #[derive(Props, Clone, PartialEq)]
 pub struct ResultsProps {
     pub results: FinalResults,
+    pub end_reason: Option<EndReason>,
+    pub quote: Option<Quote>,   // picked quote in quote mode, shown with its author and source
+    pub on_drill: Option<EventHandler<MouseEvent>>,  // practice of missed words, none if nothing was missed
 }
 
 #[component]
@@ -99,20 +106,20 @@ pub fn Results(props: ResultsProps) -> Element {
     let mut err_pts = vec![];
     let mut prev_incorrect = 0;
 
-    for (t, w, r, incorrect, _, _) in &results.graph_data {
-        wpm_pts.push((*t, *w));
-        raw_pts.push((*t, *r));
-        if *incorrect > prev_incorrect {
-            err_pts.push((*t, *w));
+    for point in &results.graph_data {
+        wpm_pts.push((point.time, point.wpm));
+        raw_pts.push((point.time, point.raw_wpm));
+        if point.incorrect > prev_incorrect {
+            err_pts.push((point.time, point.wpm));
         }
-        prev_incorrect = *incorrect;
+        prev_incorrect = point.incorrect;
     }
 
-    let x_max = results.graph_data.last().map(|(t, ..)| t.ceil().max(1.0)).unwrap_or(1.0);
+    let x_max = results.graph_data.last().map(|point| point.time.ceil().max(1.0)).unwrap_or(1.0);
     let y_max = results
         .graph_data
         .iter()
-        .map(|&(_, w, r, _, _, _)| w.max(r))
+        .map(|point| point.wpm.max(point.raw_wpm))
         .fold(0.0, f64::max)
         .ceil()
         .max(1.0);
@@ -178,6 +185,13 @@ pub fn Results(props: ResultsProps) -> Element {
                                 span { class: "info-label", "accuracy: " }
                                 span { class: "info-value", "{results.accuracy.round()}%" }
                             }
+                            if results.lenient_accuracy.round() != results.accuracy.round() {
+                                div {
+                                    class: "info-row",
+                                    span { class: "info-label", "lenient: " }
+                                    span { class: "info-value", "{results.lenient_accuracy.round()}%" }
+                                }
+                            }
                             div {
                                 class: "info-row",
                                 span { class: "info-label", "consistency: " }
@@ -191,6 +205,20 @@ pub fn Results(props: ResultsProps) -> Element {
                                     "{results.key_presses.correct}/{results.key_presses.incorrect}/{results.key_presses.extra}/{results.key_presses.missed}"
                                 }
                             }
+                            if let Some(reason) = props.end_reason {
+                                div {
+                                    class: "info-row",
+                                    span { class: "info-label", "end: " }
+                                    span { class: "info-value", "{reason.as_str()}" }
+                                }
+                            }
+                            if let Some(quote) = &props.quote {
+                                div {
+                                    class: "info-row",
+                                    span { class: "info-label", "quote: " }
+                                    span { class: "info-value", {quote.attribution().unwrap_or_else(|| quote.id.clone())} }
+                                }
+                            }
                         }
                     }
                 }
@@ -218,6 +246,14 @@ pub fn Results(props: ResultsProps) -> Element {
                     }
                 }
             }
+            // Missed words practice
+            if let Some(on_drill) = props.on_drill {
+                button {
+                    class: "restart-button practice-button",
+                    onclick: move |event| on_drill.call(event),
+                    "practice missed words"
+                }
+            }
         }
     }
 }
@@ -1,13 +1,19 @@
-use std::rc::Rc;
+use std::{
+    rc::Rc,
+    time::Duration
+};
 use dioxus::prelude::*;
+use gloo_timers::future::TimeoutFuture;
 use typingcore::{
-    results::{Key},
+    results::{Key, Word},
     Test,
     Config,
     GameMode,
+    WordStream,
+    Clock,
+    BrowserClock,
 };
 use super::letter::{LetterState, Letter};
-use web_sys::js_sys::Date;
 
 fn convert_key(event: Event<KeyboardData>) -> Key {
 
@@ -16,9 +22,10 @@ fn convert_key(event: Event<KeyboardData>) -> Key {
         "Backspace" => Key::Backspace,
         "Escape" => Key::Escape,
         " " => Key::Space,
+        "Tab" => Key::Tab,
         key => {
-            if key.len() == 1 {
-                // Single character keys (e.g., "a", "A", "1", "@") are treated as chars
+            if key.chars().count() == 1 {
+                // Single character keys (e.g., "a", "A", "1", "@", "á") are treated as chars
                 Key::Char(key.chars().next().unwrap())
             } else {
                 // Other keys (e.g., "Shift", "Control", "F1") are treated as Other
@@ -28,6 +35,15 @@ fn convert_key(event: Event<KeyboardData>) -> Key {
     }
 }
 
+// time key was pressed, in time base of browser clock, so test timing is the same as in cli
+fn event_time(event: &Event<KeyboardData>) -> Duration {
+    event
+        .data
+        .downcast::<web_sys::KeyboardEvent>()
+        .map(|event| Duration::from_secs_f64(event.time_stamp() / 1000.0))
+        .unwrap_or_else(|| BrowserClock.now())
+}
+
 fn highlight_word(typed: &str, text: &str, is_current: bool) -> Vec<(char, Option<LetterState>)> {
 
 
@@ -52,7 +68,9 @@ fn highlight_word(typed: &str, text: &str, is_current: bool) -> Vec<(char, Optio
         i += 1;
     }
 
+    // line break typed in code is shown by sign
     for &char in &typed_chars[i..] {
+        let char = if char == '\n' { '↵' } else { char };
         result.push((char, Some(LetterState::Incorrect)));
     }
 
@@ -72,23 +90,67 @@ fn highlight_word(typed: &str, text: &str, is_current: bool) -> Vec<(char, Optio
     result
 }
 
+// code keeps its lines and indentation, tokens of line are separated by space
+fn code_letters(words: &[Word], current_word: Option<usize>) -> Vec<(char, Option<LetterState>)> {
+    let mut result = Vec::new();
+
+    for (i, word) in words.iter().enumerate() {
+        let line_start = i == 0 || words[i - 1].text.ends_with('\n') || words[i - 1].is_indent();
+        if !line_start {
+            result.push((' ', None));
+        }
+
+        for (char, state) in highlight_word(&word.progress, &word.text, current_word == Some(i)) {
+            match (char, state) {
+                // caret at line break
+                ('\n', Some(LetterState::Active)) => result.extend([(' ', state), ('\n', None)]),
+                _ => result.push((char, state)),
+            }
+        }
+    }
+
+    result
+}
+
 #[derive(Props, Clone, PartialEq)]
 pub struct TestProps {
     pub test: Signal<Option<Test>>,
     pub config: Config,
     pub complete: Signal<bool>,
-    pub test_start: Signal<Option<f64>>,
     pub words: Signal<Option<Rc<Vec<String>>>>,
+    pub stream: Signal<Option<WordStream>>,
 }
 
 #[component]
 pub fn TestComponent(mut props: TestProps) -> Element {
     let game_mode = props.config.mode;
+
+    // time limit is checked by core, here it only has to be polled while no keys are pressed
+    use_future(move || async move {
+        loop {
+            TimeoutFuture::new(100).await;
+            let mut test = props.test;
+            let mut complete = props.complete;
+            if *complete.peek() || test.peek().is_none() {
+                continue;
+            }
+            let finished = match *test.write() {
+                Some(ref mut test_state) => {
+                    test_state.tick(BrowserClock.now());
+                    test_state.complete
+                }
+                None => false,
+            };
+            if finished {
+                complete.set(true);
+            }
+        }
+    });
+
     let on_keydown = move |event: Event<KeyboardData>| {
         if *props.complete.read() {
             if game_mode == GameMode::Zen {
                 props.test.set(None);
-                props.test_start.set(None);
                 props.complete.set(false);
             }
             return;
@@ -99,22 +161,28 @@ pub fn TestComponent(mut props: TestProps) -> Element {
             if let Some(ref words) = props.words.as_ref() {
                 if !words.is_empty() {
                     // Clone the words to avoid moving
-                    props.test.set(Some(Test::new(words.clone().to_vec(), &props.config)));
-                    props.test_start.set(Some(Date::now()));
+                    props.test.set(Some(Test::with_clock(words.clone().to_vec(), &props.config, Box::new(BrowserClock))));
                 }
             }
         }
 
-        // Handle key press if test is active
+        // tab types indentation in code mode instead of moving focus
+        if game_mode == GameMode::Code && event.key() == dioxus::prelude::Key::Tab {
+            event.prevent_default();
+        }
+
+        // Handle key press if test is active, core starts timing with first typing key and ends test on escape
         if let Some(ref mut test_state) = *props.test.write() {
             let key = convert_key(event.clone());
-            test_state.handle_key(key);
-            if event.data.code() == Code::Escape {
-                props.complete.set(true);
-            }
+            test_state.handle_key_at(key, event_time(&event));
             if test_state.complete {
                 props.complete.set(true);
             }
+
+            // append words before the end is reached
+            if let Some(ref mut stream) = *props.stream.write() {
+                stream.fill(test_state);
+            }
         }
     };
 
@@ -129,25 +197,33 @@ pub fn TestComponent(mut props: TestProps) -> Element {
                 None => rsx! { div { class:"loading", "Loading..." } },
                 Some(ref payload) => {
                     if let Some(ref test_state) = *props.test.read() {
+                        let remaining = test_state.remaining().map(|r| r.as_secs_f64().ceil());
                         rsx! {
-                            div { class: "words-container",
-                                for (i, _word) in test_state.words.iter().enumerate() {
-                                    div {
-                                        class: "word",
-                                        {
-                                            let is_current = i == test_state.current_word;
-                                            let typed = &test_state.words[i].progress;
-                                            let text = if game_mode == GameMode::Zen {
-                                                &test_state.words[i].text
-                                            } else {
-                                                &payload[i]
-                                            };
-                                            let chars = highlight_word(typed, text, is_current);
-                                            rsx! {
-                                                for (char, state) in chars {
-                                                    Letter {
-                                                        letter: char,
-                                                        state: state
+                            if let Some(remaining) = remaining {
+                                div { class: "timer", "{remaining}" }
+                            }
+                            if game_mode == GameMode::Code {
+                                div { class: "code-container",
+                                    for (char, state) in code_letters(&test_state.words, Some(test_state.current_word)) {
+                                        Letter { letter: char, state: state }
+                                    }
+                                }
+                            } else {
+                                div { class: "words-container",
+                                    for (i, _word) in test_state.words.iter().enumerate() {
+                                        div {
+                                            class: "word",
+                                            {
+                                                let is_current = i == test_state.current_word;
+                                                let typed = &test_state.words[i].progress;
+                                                let text = &test_state.words[i].text;
+                                                let chars = highlight_word(typed, text, is_current);
+                                                rsx! {
+                                                    for (char, state) in chars {
+                                                        Letter {
+                                                            letter: char,
+                                                            state: state
+                                                        }
                                                     }
                                                 }
                                             }
@@ -158,14 +234,22 @@ pub fn TestComponent(mut props: TestProps) -> Element {
                         }
                 } else {
                         rsx! {
-                            div { class: "words-container",
-                                for word in payload.iter() {
-                                    div {
-                                        class: "word",
-                                        for char in word.chars() {
-                                            Letter {
-                                                letter: char,
-                                                state: None
+                            if game_mode == GameMode::Code {
+                                div { class: "code-container",
+                                    for (char, state) in code_letters(&payload.iter().cloned().map(Word::from).collect::<Vec<_>>(), None) {
+                                        Letter { letter: char, state: state }
+                                    }
+                                }
+                            } else {
+                                div { class: "words-container",
+                                    for word in payload.iter() {
+                                        div {
+                                            class: "word",
+                                            for char in word.chars() {
+                                                Letter {
+                                                    letter: char,
+                                                    state: None
+                                                }
                                             }
                                         }
                                     }
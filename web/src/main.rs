@@ -1,7 +1,7 @@
 use dioxus::prelude::*;
 use dioxus_toast::{ToastFrame, ToastInfo, ToastManager};
 use web_sys::HtmlLinkElement;
-use typingcore::{Schemes, BASE_PATH};
+use typingcore::BASE_PATH;
 use web_sys::wasm_bindgen::JsCast;
 
 use components::*;
@@ -15,6 +15,8 @@ const ICON_32: Asset = asset!("/public/images/icons/favicon-32x32.png");
 const ICON_16: Asset = asset!("/public/images/icons/favicon-16x16.png");
 const MANIFEST : Asset = asset!("/assets/site.webmanifest");
 
+pub const DEFAULT_SCHEME: &str = "catppuccin";     // scheme used until user picks another one
+
 fn main() {
     dioxus::launch(App);
 }
@@ -50,7 +52,7 @@ fn App() -> Element {
                 }
             }
         }
-        Schemes::Catppuccin.as_str().to_string()
+        DEFAULT_SCHEME.to_string()
     });
 
     // Effect to handle scheme loading and updating on mount and when current_scheme changes
@@ -1,11 +1,13 @@
 use dioxus::prelude::*;
 use dioxus_toast::{ToastInfo, ToastManager};
-use typingcore::{Config, GameMode, validate_config, language_from_str, Language, WordsLanguages, QuotesLanguages, Level, Schemes, BASE_PATH};
+use typingcore::{Config, GameMode, validate_config, language_from_str, pack_names, is_code_pack, PackKind, Level, QuoteLength, list_quotes, search_quotes, BASE_PATH};
 use web_sys::{console, window, HtmlLinkElement};
 use web_sys::wasm_bindgen::JsCast;
 use serde::{Serialize, Deserialize};
 use uuid::Uuid;
 
+use crate::DEFAULT_SCHEME;
+
 #[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct StoredConfig {
     pub id: String,
@@ -73,7 +75,7 @@ pub fn Settings() -> Element {
                 }
             }
         }
-        Schemes::Catppuccin.as_str().to_string() 
+        DEFAULT_SCHEME.to_string()
     });
 
     use_effect(move || {
@@ -104,20 +106,36 @@ pub fn Settings() -> Element {
     });
 
     let language_options = match current_config.read().config.mode {
-        GameMode::Words => WordsLanguages::all()
-            .iter()
-            .map(|lang| lang.as_str().to_string())
-            .collect::<Vec<_>>(),
-        GameMode::Quote => QuotesLanguages::all()
-            .iter()
-            .map(|lang| lang.as_str().to_string())
-            .collect::<Vec<_>>(),
-        GameMode::Zen => vec!["en".to_string()], // No language options for Zen
-    };
-    let current_language = match current_config.read().config.language {
-        Language::Words(lang) => lang.as_str(),
-        Language::Quotes(lang) => lang.as_str(),
+        GameMode::Words => pack_names(PackKind::Words),
+        GameMode::Quote => pack_names(PackKind::Quotes),
+        GameMode::Code => pack_names(PackKind::Quotes).into_iter().filter(|name| is_code_pack(name)).collect(),
+        GameMode::Zen | GameMode::Book => vec!["en".to_string()], // No language options for Zen, book is not available in browser
     };
+    let current_language = current_config.read().config.language.as_str().to_string();
+
+    // quotes of saved quotes language for quote picker, narrowed by search query
+    let mut quote_query = use_signal(String::new);
+    let quotes = use_resource(move || async move {
+        let config = current_config.read().config.clone();
+        if !matches!(config.mode, GameMode::Quote | GameMode::Code) {
+            return Vec::new();
+        }
+
+        let response = list_quotes(&config.language).await;
+        if let Some((Level::Error, msg)) = &response.message {
+            console::log_1(&msg.as_str().into());
+        }
+        response.payload
+    });
+    let all_quotes = quotes.read().clone().unwrap_or_default();
+    let mut quote_options = search_quotes(&all_quotes, &quote_query.read());
+
+    // chosen quote stays selectable, so search does not reset it on save
+    if let Some(id) = current_config.read().config.quote_id.clone() {
+        if !quote_options.iter().any(|quote| quote.id == id) {
+            quote_options.extend(all_quotes.into_iter().filter(|quote| quote.id == id));
+        }
+    }
     rsx! {
         document::Link { rel: "stylesheet", href: SETTINGS_CSS}
         main {
@@ -199,6 +217,7 @@ pub fn Settings() -> Element {
                         new_config.config.mode = match mode.0[0].as_str() {
                             "words" => GameMode::Words,
                             "quote" => GameMode::Quote,
+                            "code" => GameMode::Code,
                             "zen" => GameMode::Zen,
                             _ => GameMode::Words,
                         };
@@ -229,10 +248,35 @@ pub fn Settings() -> Element {
                     if new_config.config.mode == GameMode::Words {
                         new_config.config.punctuation = event.data.values().get("punctuation").map(|v| v == "on").unwrap_or(false);
                         new_config.config.numbers = event.data.values().get("numbers").map(|v| v == "on").unwrap_or(false);
+                        new_config.config.weighted = event.data.values().get("weighted").map(|v| v == "on").unwrap_or(false);
+                    }
+
+                    // Parse top words, 0 = all words
+                    if let Some(top_words) = event.data.values().get("top-words") {
+                        if let Ok(num) = top_words.0[0].parse::<usize>() {
+                            new_config.config.top_words = if num == 0 { None } else { Some(num) };
+                        }
                     }
 
+                    // Parse quote length, any length = none
+                    new_config.config.quote_length = match new_config.config.mode {
+                        GameMode::Quote | GameMode::Code => event.data.values().get("quote-length").and_then(|v| QuoteLength::from_name(&v.0[0])),
+                        _ => None,
+                    };
+
+                    // Parse picked quote, random = none, quotes are listed only for saved language
+                    new_config.config.quote_id = match new_config.config.mode {
+                        GameMode::Quote | GameMode::Code if new_config.config.language == current_config.read().config.language => event.data.values()
+                            .get("quote-id")
+                            .map(|v| v.0[0].to_string())
+                            .filter(|id| id != "random"),
+                        _ => None,
+                    };
+
                     new_config.config.backtrack = event.data.values().get("backtrack").map(|v| v == "on").unwrap_or(false);
                     new_config.config.death = event.data.values().get("death").map(|v| v == "on").unwrap_or(false);
+                    new_config.config.lenient_accents = new_config.config.mode != GameMode::Zen && event.data.values().get("lenient-accents").map(|v| v == "on").unwrap_or(false);
+                    new_config.config.daily = new_config.config.mode != GameMode::Zen && event.data.values().get("daily").map(|v| v == "on").unwrap_or(false);
 
                     
                     let config_response = validate_config(new_config.config);
@@ -298,6 +342,7 @@ pub fn Settings() -> Element {
                                 let new_mode = match event.value().as_str() {
                                     "words" => GameMode::Words,
                                     "quote" => GameMode::Quote,
+                                    "code" => GameMode::Code,
                                     "zen" => GameMode::Zen,
                                     _ => GameMode::Words, // fallback in case of unexpected value
                                 };
@@ -309,6 +354,7 @@ pub fn Settings() -> Element {
                             },
                             option { value: "words", selected: current_config.read().config.mode == GameMode::Words, "words" }
                         option { value: "quote", selected: current_config.read().config.mode == GameMode::Quote, "quote" }
+                        option { value: "code", selected: current_config.read().config.mode == GameMode::Code, "code" }
                         option { value: "zen", selected: current_config.read().config.mode == GameMode::Zen, "zen" }
                     }
                 }
@@ -360,6 +406,58 @@ pub fn Settings() -> Element {
                             checked:"{current_config.read().config.numbers}"
                         } 
                     }
+                    label { "top words (optional)",
+                        input {
+                            name: "top-words",
+                            r#type: "number",
+                            min: "0",
+                            value: "{current_config.read().config.top_words.unwrap_or(0)}",
+                        }
+                    }
+                    label {"weighted by frequency",
+                        input {
+                            name: "weighted",
+                            r#type: "checkbox",
+                            checked:"{current_config.read().config.weighted}"
+                        }
+                    }
+                }
+
+                if matches!(current_config.read().config.mode, GameMode::Quote | GameMode::Code) {
+                    label { "quote length",
+                        select {
+                            name: "quote-length",
+                            option { value: "any", selected: current_config.read().config.quote_length.is_none(), "any" }
+                            for length in QuoteLength::ALL {
+                                option {
+                                    value: "{length.as_str()}",
+                                    selected: current_config.read().config.quote_length == Some(length),
+                                    "{length.as_str()}"
+                                }
+                            }
+                        }
+                    }
+                    label { "quote search",
+                        input {
+                            r#type: "text",
+                            placeholder: "text, author or length",
+                            value: "{quote_query}",
+                            oninput: move |event| quote_query.set(event.value()),
+                        }
+                    }
+                    label { "quote",
+                        select {
+                            name: "quote-id",
+                            option { value: "random", selected: current_config.read().config.quote_id.is_none(), "random" }
+                            for quote in quote_options {
+                                option {
+                                    value: "{quote.id}",
+                                    selected: current_config.read().config.quote_id.as_deref() == Some(quote.id.as_str()),
+                                    {quote.attribution().map_or(quote.id.clone(), |attribution| format!("{} ({})", quote.id, attribution))}
+                                }
+                            }
+                        }
+                    }
                 }
 
                 label {"backtrack", 
@@ -377,6 +475,26 @@ pub fn Settings() -> Element {
                         checked:"{current_config.read().config.death}"
                     } 
                 }
+
+                if current_config.read().config.mode != GameMode::Zen {
+                    label {"lenient accents",
+                        input {
+                            name: "lenient-accents",
+                            r#type: "checkbox",
+                            checked:"{current_config.read().config.lenient_accents}"
+                        }
+                    }
+                }
+
+                if current_config.read().config.mode != GameMode::Zen {
+                    label {"daily challenge",
+                        input {
+                            name: "daily",
+                            r#type: "checkbox",
+                            checked:"{current_config.read().config.daily}"
+                        }
+                    }
+                }
                 input {
                     r#type: "submit",
                     value: "save",
@@ -392,11 +510,11 @@ pub fn Settings() -> Element {
                             let new_scheme = event.value();
                             current_scheme.set(new_scheme);
                         },
-                        for scheme in Schemes::all() {
+                        for scheme in pack_names(PackKind::Schemes) {
                             option {
-                            value: "{scheme.as_str()}",
-                            selected: scheme.as_str() == *current_scheme.read(),
-                            "{scheme.as_str()}"
+                            value: "{scheme}",
+                            selected: scheme == *current_scheme.read(),
+                            "{scheme}"
                             }
                         }
                     }
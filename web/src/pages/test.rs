@@ -2,15 +2,30 @@ use std::rc::Rc;
 use dioxus::prelude::*;
 use web_sys::{console, window};
 use typingcore::{
+    Config,
     Level,
-    generate_content, 
+    GeneratorResponse,
+    generate_content,
+    generate_quote,
+    generate_stream,
+    generate_practice,
+    generate_practice_stream,
+    generate_drill,
+    weak_targets,
     process_results,
     RawResults,
     results::{
         FinalResults,
     },
     Test,
-    GameMode
+    GameMode,
+    WordStream,
+    HistoryRecord,
+    TestKind,
+    HistoryStore,
+    LocalStorageStore,
+    Quote,
+    today
 };
 use crate::pages::settings::{StoredConfig};
 use crate::components::{
@@ -18,6 +33,27 @@ use crate::components::{
     TestComponent
 };
 
+// how many times every missed word is repeated in drill
+const DRILL_REPEAT: usize = 3;
+
+// test content, quote and code modes return picked quote too
+async fn generate_words(config: &Config) -> (GeneratorResponse, Option<Quote>) {
+    let split = match config.mode {
+        GameMode::Quote => Quote::words,
+        GameMode::Code => Quote::code_words,
+        _ => return (generate_content(config).await, None),
+    };
+
+    let response = generate_quote(config).await;
+    let words = response.payload.as_ref().map(split).unwrap_or_default();
+    (GeneratorResponse { payload: words, message: response.message }, response.payload)
+}
+
+// config of new text, daily one is seeded by day it is generated on and saved under it
+fn dated(config: &Config) -> Config {
+    Config { day: config.daily.then(today), ..config.clone() }
+}
+
 #[component]
 pub fn TypingTest() -> Element { 
     let mut current_config = use_signal(|| {
@@ -34,44 +70,120 @@ pub fn TypingTest() -> Element {
     });
 
     let mut test = use_signal(|| None::<Test>);
-    let mut test_start = use_signal(|| None::<f64>);
     let mut complete = use_signal(|| false);
     let mut words = use_signal(|| None::<Rc<Vec<String>>>);
+    let mut stream = use_signal(|| None::<WordStream>);
     let mut final_results = use_signal(|| None::<FinalResults>);
+    let mut quote = use_signal(|| None::<Quote>);
+    let mut kind = use_signal(|| TestKind::Regular);
+    let mut day = use_signal(|| None::<u64>);
 
     let restart_test = move |_| {
         // Reset all relevant signals to their initial states
         test.set(None);
-        test_start.set(None);
         complete.set(false);
         final_results.set(None);
+        kind.set(TestKind::Regular);
         
         // Regenerate content based on current config
-        let config = current_config.read().config.clone();
+        let config = dated(&current_config.read().config);
+        day.set(config.day);
         spawn(async move {
-            let generation_response = generate_content(&config).await;
+            let (generation_response, picked_quote) = generate_words(&config).await;
             if let Some((Level::Error, msg)) = &generation_response.message {
                 console::log_1(&msg.as_str().into());
             }
             words.set(Some(Rc::new(generation_response.payload)));
+            quote.set(picked_quote);
+
+            let stream_response = generate_stream(&config).await;
+            if let Some((Level::Error, msg)) = &stream_response.message {
+                console::log_1(&msg.as_str().into());
+            }
+            stream.set(stream_response.payload);
+        });
+    };
+
+    let practice_mistakes = move |_| {
+        let targets = match final_results.read().as_ref() {
+            Some(results) => weak_targets(&results.errors, 5),
+            None => return,
+        };
+
+        test.set(None);
+        complete.set(false);
+        final_results.set(None);
+
+        // new words biased toward mistyped letters, stream of timed test keeps the bias
+        let config = dated(&current_config.read().config);
+        kind.set(if targets.is_empty() { TestKind::Regular } else { TestKind::Practice });
+        day.set(config.day);
+        spawn(async move {
+            let generation_response = generate_practice(&config, &targets).await;
+            if let Some((_, msg)) = &generation_response.message {
+                console::log_1(&msg.as_str().into());
+            }
+            words.set(Some(Rc::new(generation_response.payload)));
+            quote.set(None);
+
+            let stream_response = generate_practice_stream(&config, &targets).await;
+            if let Some((Level::Error, msg)) = &stream_response.message {
+                console::log_1(&msg.as_str().into());
+            }
+            stream.set(stream_response.payload);
         });
     };
 
+    // new test from mistyped and slow words of finished one
+    let drill_words = use_memo(move || {
+        test.read().as_ref().filter(|t| t.complete).map(|t| generate_drill(&RawResults::from(t), DRILL_REPEAT).payload).unwrap_or_default()
+    });
+
+    let practice_missed = move |_| {
+        let drill = drill_words.read().clone();
+        test.set(None);
+        complete.set(false);
+        final_results.set(None);
+        words.set(Some(Rc::new(drill)));
+        stream.set(None);
+        quote.set(None);
+        kind.set(TestKind::Drill);
+        day.set(None);
+    };
+
     use_effect(move || {
-        let config = current_config.read().config.clone();
+        let config = dated(&current_config.read().config);
+        kind.set(TestKind::Regular);
+        day.set(config.day);
         spawn(async move {
-            let generation_response = generate_content(&config).await;
+            let (generation_response, picked_quote) = generate_words(&config).await;
             if let Some((Level::Error, msg)) = &generation_response.message {
                 console::log_1(&msg.as_str().into());
             }
             words.set(Some(Rc::new(generation_response.payload)));
+            quote.set(picked_quote);
+
+            let stream_response = generate_stream(&config).await;
+            if let Some((Level::Error, msg)) = &stream_response.message {
+                console::log_1(&msg.as_str().into());
+            }
+            stream.set(stream_response.payload);
         });
     });
 
     use_effect(move || {
         if *complete.read() && test.read().is_some() && current_config.read().config.mode != GameMode::Zen {
             let raw_results = RawResults::from(test.read().as_ref().unwrap());
-            final_results.set(Some(process_results(raw_results).payload));
+            let results = process_results(raw_results).payload;
+
+            // save finished test to history
+            let words = test.read().as_ref().unwrap().words.iter().map(|w| w.text.clone()).collect();
+            let record = HistoryRecord::new(current_config.read().config.clone(), results.clone(), words, *day.peek()).with_kind(*kind.peek());
+            if let Some((Level::Error, msg)) = LocalStorageStore::default().save(&record).message {
+                console::log_1(&msg.as_str().into());
+            }
+
+            final_results.set(Some(results));
         }
     });
 
@@ -80,14 +192,19 @@ pub fn TypingTest() -> Element {
             {
                 if final_results.read().is_some() {
                     rsx! {
-                        Results { results: final_results.read().as_ref().unwrap().clone() }
+                        Results {
+                            results: final_results.read().as_ref().unwrap().clone(),
+                            end_reason: test.read().as_ref().and_then(|t| t.end_reason),
+                            quote: quote.read().clone(),
+                            on_drill: if drill_words.read().is_empty() { None } else { Some(EventHandler::new(practice_missed)) }
+                        }
                     }
                 } else {
                     rsx! {
                         TestComponent {
                             words: words,
+                            stream: stream,
                             test: test,
-                            test_start: test_start,
                             complete: complete,
                             config: current_config.read().config.clone()
                         }
@@ -100,6 +217,14 @@ pub fn TypingTest() -> Element {
                 onclick: restart_test,
                 "restart"
             }
+            if current_config.read().config.mode == GameMode::Words
+                && final_results.read().as_ref().is_some_and(|r| !r.errors.is_empty()) {
+                button {
+                    class: "restart-button practice-button",
+                    onclick: practice_mistakes,
+                    "practice mistakes"
+                }
+            }
         }
     }
 }